@@ -0,0 +1,103 @@
+/// A linear congruential generator, for building repeatable inputs in tests
+/// and benchmarks. Not suitable for anything needing good randomness.
+#[derive(Debug, Clone)]
+pub struct Lcg {
+    state: u64,
+}
+
+impl Lcg {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// The next number, below `range`.
+    pub fn below(&mut self, range: u64) -> u64 {
+        self.state = self
+            .state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.state >> 33) % range
+    }
+}
+
+/// Day 8 puzzle input listing `count` junction boxes at pseudo-random
+/// positions in a cube `size` wide, one `X,Y,Z` line each.
+pub fn junction_boxes(count: usize, size: u64, seed: u64) -> String {
+    let mut rng = Lcg::new(seed);
+    (0..count)
+        .map(|_| {
+            let [x, y, z] = [(); 3].map(|_| rng.below(size));
+            format!("{x},{y},{z}\n")
+        })
+        .collect()
+}
+
+/// One line of the day 10 manual: a machine with `lights` lights and counters
+/// and `buttons` distinct buttons, each wired to about a third of them. Its
+/// lights and joltages are those reached by pressing each button up to
+/// `max_presses` times. There must be at least `buttons` non-empty sets of
+/// lights.
+pub fn machine(lights: usize, buttons: usize, max_presses: u64, rng: &mut Lcg) -> String {
+    let mut wiring: Vec<Vec<usize>> = Vec::with_capacity(buttons);
+    while wiring.len() < buttons {
+        let button = (0..lights)
+            .filter(|_| rng.below(3) == 0)
+            .collect::<Vec<_>>();
+        if !button.is_empty() && !wiring.contains(&button) {
+            wiring.push(button);
+        }
+    }
+    let mut joltages = vec![0; lights];
+    let mut on = vec![false; lights];
+    for button in &wiring {
+        let presses = rng.below(max_presses + 1);
+        for &i in button {
+            on[i] ^= presses % 2 == 1;
+            joltages[i] += presses;
+        }
+    }
+
+    let lights = on.iter().map(|&on| if on { '#' } else { '.' });
+    let mut line = format!("[{}]", lights.collect::<String>());
+    for button in &wiring {
+        line.push_str(&format!(" ({})", join(button)));
+    }
+    line.push_str(&format!(" {{{}}}", join(&joltages)));
+    line
+}
+
+fn join(values: &[impl ToString]) -> String {
+    values
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeatable() {
+        let draw = |seed| {
+            let mut rng = Lcg::new(seed);
+            (0..100).map(|_| rng.below(10)).collect::<Vec<_>>()
+        };
+        assert_eq!(draw(3), draw(3));
+        assert_ne!(draw(3), draw(4));
+        assert!(draw(3).iter().all(|&n| n < 10));
+    }
+
+    #[test]
+    fn generators() {
+        let boxes = junction_boxes(3, 10, 0);
+        assert_eq!(boxes.lines().count(), 3);
+        assert_eq!(boxes, junction_boxes(3, 10, 0));
+
+        let line = machine(4, 3, 1, &mut Lcg::new(0));
+        assert!(line.starts_with('['));
+        assert_eq!(line.matches('(').count(), 3);
+        assert!(line.ends_with('}'));
+    }
+}
@@ -0,0 +1,102 @@
+/// Classification of a single line of input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Line<'a> {
+    Blank,
+    Comment,
+    Content(&'a str),
+}
+
+/// Classify a line, stripping any trailing carriage return left by `\r\n` endings.
+/// Lines starting with `comment` (after leading whitespace) are comments.
+pub fn classify<'a>(line: &'a str, comment: Option<&str>) -> Line<'a> {
+    let line = line.trim_end_matches(['\r', '\n']);
+    let trimmed = line.trim_start();
+    if trimmed.is_empty() {
+        Line::Blank
+    } else if comment.is_some_and(|prefix| trimmed.starts_with(prefix)) {
+        Line::Comment
+    } else {
+        Line::Content(line)
+    }
+}
+
+/// Iterator over the blank line separated sections of an input.
+///
+/// Accepts `\n` and `\r\n` line endings, any number of blank lines between
+/// (or before and after) sections and, optionally, comment lines which are
+/// dropped without ending the current section. Each section is returned as
+/// its content lines.
+#[derive(Debug, Clone)]
+pub struct Sections<'a> {
+    lines: std::str::Lines<'a>,
+    comment: Option<&'a str>,
+}
+
+pub fn sections(input: &str) -> Sections<'_> {
+    Sections {
+        lines: input.lines(),
+        comment: None,
+    }
+}
+
+impl<'a> Sections<'a> {
+    /// Treat lines starting with `prefix` as comments.
+    pub fn with_comments(self, prefix: &'a str) -> Self {
+        Self {
+            comment: Some(prefix),
+            ..self
+        }
+    }
+}
+
+impl<'a> Iterator for Sections<'a> {
+    type Item = Vec<&'a str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut section = Vec::new();
+        for line in self.lines.by_ref() {
+            match classify(line, self.comment) {
+                Line::Content(line) => section.push(line),
+                Line::Blank if !section.is_empty() => break,
+                Line::Blank | Line::Comment => {}
+            }
+        }
+        if section.is_empty() {
+            None
+        } else {
+            Some(section)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unix_line_endings() {
+        let result = sections("a\nb\n\nc\n").collect::<Vec<_>>();
+        assert_eq!(result, [vec!["a", "b"], vec!["c"]]);
+    }
+
+    #[test]
+    fn windows_line_endings() {
+        let result = sections("a\r\nb\r\n\r\nc\r\n").collect::<Vec<_>>();
+        assert_eq!(result, [vec!["a", "b"], vec!["c"]]);
+    }
+
+    #[test]
+    fn extra_blank_lines() {
+        let result = sections("\n \na\n\n\n\t\nb\n\n").collect::<Vec<_>>();
+        assert_eq!(result, [vec!["a"], vec!["b"]]);
+    }
+
+    #[test]
+    fn comments() {
+        let input = "# header\na\n  # note\nb\n\n# only a comment\n\nc";
+        let result = sections(input).with_comments("#").collect::<Vec<_>>();
+        assert_eq!(result, [vec!["a", "b"], vec!["c"]]);
+        let result = sections(input).collect::<Vec<_>>();
+        assert_eq!(result.len(), 3);
+    }
+}
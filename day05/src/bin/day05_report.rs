@@ -0,0 +1,16 @@
+//! Usage: `day05_report [--json | --ranges]`
+//!
+//! Prints a CSV of each ID and the ranges covering it, a CSV of hit counts per
+//! range with `--ranges`, or both as a single JSON document with `--json`.
+
+use day05::{INPUT, part1, report};
+
+fn main() -> Result<(), part1::Error> {
+    let report = report::build(INPUT)?;
+    match std::env::args().nth(1).as_deref() {
+        Some("--json") => println!("{}", report.to_json()),
+        Some("--ranges") => print!("{}", report.ranges_csv()),
+        _ => print!("{}", report.ids_csv()),
+    }
+    Ok(())
+}
@@ -0,0 +1,41 @@
+//! Usage: `day05_stream [--count] [FILE]`
+//!
+//! Checks IDs from FILE or stdin one line at a time. The input uses the puzzle
+//! format: the fresh ranges, a blank line, then one ID per line. Prints a
+//! verdict for each ID followed by the totals, or only the totals with `--count`.
+
+use day05::stream::{Checker, Error, Tally};
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+};
+
+fn main() -> Result<(), Error> {
+    let mut count_only = false;
+    let mut path = None;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--count" => count_only = true,
+            _ => path = Some(arg),
+        }
+    }
+
+    let mut reader: Box<dyn BufRead> = match path {
+        Some(path) => Box::new(BufReader::new(File::open(path)?)),
+        None => Box::new(io::stdin().lock()),
+    };
+    let checker = Checker::from_reader(&mut reader)?;
+
+    let mut out = BufWriter::new(io::stdout().lock());
+    let mut tally = Tally::default();
+    for result in checker.check_lines(reader) {
+        let (id, verdict) = result?;
+        tally.add(verdict);
+        if !count_only {
+            writeln!(out, "{id} {verdict}")?;
+        }
+    }
+    writeln!(out, "fresh: {}", tally.fresh)?;
+    writeln!(out, "spoiled: {}", tally.spoiled)?;
+    Ok(())
+}
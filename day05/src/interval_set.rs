@@ -0,0 +1,205 @@
+use std::ops::RangeInclusive;
+
+type Id = u64;
+
+/// A set of IDs stored as sorted, disjoint and non-adjacent inclusive ranges.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    ranges: Vec<RangeInclusive<Id>>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a range to the set, merging it with any ranges it overlaps or touches.
+    pub fn insert(&mut self, range: RangeInclusive<Id>) {
+        if range.is_empty() {
+            return;
+        }
+        // First range that could merge with the new one (ends at or after start - 1)
+        let first = self
+            .ranges
+            .partition_point(|r| r.end().saturating_add(1) < *range.start());
+        // One past the last range that could merge (starts at or before end + 1)
+        let last = self
+            .ranges
+            .partition_point(|r| *r.start() <= range.end().saturating_add(1));
+        let merged = if first < last {
+            let start = *range.start().min(self.ranges[first].start());
+            let end = *range.end().max(self.ranges[last - 1].end());
+            start..=end
+        } else {
+            range
+        };
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    /// Returns the merged range containing `id`, if any.
+    pub fn get(&self, id: Id) -> Option<&RangeInclusive<Id>> {
+        let index = self.ranges.partition_point(|r| *r.end() < id);
+        self.ranges.get(index).filter(|r| r.contains(&id))
+    }
+
+    pub fn contains(&self, id: Id) -> bool {
+        self.get(id).is_some()
+    }
+
+    /// Total number of IDs covered by the set. This is a `u128` because a set
+    /// covering every ID holds one more than `u64::MAX`.
+    pub fn len(&self) -> u128 {
+        self.ranges
+            .iter()
+            .map(|r| (r.end() - r.start()) as u128 + 1)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Iterate over the disjoint ranges in ascending order.
+    pub fn iter(&self) -> std::slice::Iter<'_, RangeInclusive<Id>> {
+        self.ranges.iter()
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.iter().chain(other.iter()).cloned().collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut result = Vec::new();
+        let (mut a, mut b) = (self.iter().peekable(), other.iter().peekable());
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            let start = *x.start().max(y.start());
+            let end = *x.end().min(y.end());
+            if start <= end {
+                result.push(start..=end);
+            }
+            if x.end() < y.end() {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        Self { ranges: result }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = Vec::new();
+        let mut others = other.iter().peekable();
+        for range in self.iter() {
+            let mut start = *range.start();
+            let end = *range.end();
+            // Skip ranges entirely before this one
+            while others.next_if(|r| *r.end() < start).is_some() {}
+            let mut remaining = true;
+            while let Some(r) = others.peek() {
+                if *r.start() > end {
+                    break;
+                }
+                if *r.start() > start {
+                    result.push(start..=*r.start() - 1);
+                }
+                if *r.end() >= end {
+                    remaining = false;
+                    break;
+                }
+                start = *r.end() + 1;
+                others.next();
+            }
+            if remaining {
+                result.push(start..=end);
+            }
+        }
+        Self { ranges: result }
+    }
+}
+
+impl FromIterator<RangeInclusive<Id>> for IntervalSet {
+    /// Build the set by sorting the ranges and sweeping once to merge them.
+    fn from_iter<T: IntoIterator<Item = RangeInclusive<Id>>>(iter: T) -> Self {
+        let mut ranges = iter
+            .into_iter()
+            .filter(|r| !r.is_empty())
+            .collect::<Vec<_>>();
+        ranges.sort_unstable_by_key(|r| *r.start());
+        let ranges =
+            ranges
+                .into_iter()
+                .fold(Vec::<RangeInclusive<Id>>::new(), |mut merged, range| {
+                    match merged.last_mut() {
+                        Some(last) if *range.start() <= last.end().saturating_add(1) => {
+                            if range.end() > last.end() {
+                                *last = *last.start()..=*range.end();
+                            }
+                        }
+                        _ => merged.push(range),
+                    }
+                    merged
+                });
+        Self { ranges }
+    }
+}
+
+impl<'a> IntoIterator for &'a IntervalSet {
+    type Item = &'a RangeInclusive<Id>;
+    type IntoIter = std::slice::Iter<'a, RangeInclusive<Id>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[RangeInclusive<Id>]) -> IntervalSet {
+        ranges.iter().cloned().collect()
+    }
+
+    #[test]
+    fn merges_overlapping_and_adjacent() {
+        let s = set(&[3..=5, 10..=14, 16..=20, 12..=18, 6..=6]);
+        assert_eq!(s.iter().cloned().collect::<Vec<_>>(), [3..=6, 10..=20]);
+        assert_eq!(s.len(), 15);
+    }
+
+    #[test]
+    fn len_of_every_id() {
+        let mut s = IntervalSet::new();
+        s.insert(0..=u64::MAX);
+        assert_eq!(s.len(), u64::MAX as u128 + 1);
+        assert_eq!(set(&[0..=0, 2..=u64::MAX]).len(), u64::MAX as u128);
+    }
+
+    #[test]
+    fn insert_matches_from_iter() {
+        let ranges = [3..=5, 10..=14, 16..=20, 12..=18, 1..=1, 22..=30, 6..=9];
+        let mut s = IntervalSet::new();
+        for range in ranges.iter().cloned() {
+            s.insert(range);
+        }
+        assert_eq!(s, set(&ranges));
+    }
+
+    #[test]
+    fn contains() {
+        let s = set(&[3..=5, 10..=14]);
+        let found = (0..16).filter(|&id| s.contains(id)).collect::<Vec<_>>();
+        assert_eq!(found, [3, 4, 5, 10, 11, 12, 13, 14]);
+        assert_eq!(s.get(12), Some(&(10..=14)));
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = set(&[1..=10, 20..=30]);
+        let b = set(&[5..=22, 28..=40]);
+        assert_eq!(a.union(&b).iter().collect::<Vec<_>>(), [&(1..=40)]);
+        assert_eq!(a.intersection(&b), set(&[5..=10, 20..=22, 28..=30]));
+        assert_eq!(a.difference(&b), set(&[1..=4, 23..=27]));
+        assert_eq!(b.difference(&a), set(&[11..=19, 31..=40]));
+    }
+}
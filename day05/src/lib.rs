@@ -1,5 +1,8 @@
+pub mod interval_set;
 pub mod part1;
 pub mod part2;
+pub mod report;
+pub mod stream;
 
 #[cfg(input_txt)]
 pub const INPUT: &str = include_str!("../input.txt");
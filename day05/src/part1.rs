@@ -1,3 +1,5 @@
+use crate::interval_set::IntervalSet;
+use common::sections::sections;
 use std::{num::ParseIntError, ops::RangeInclusive};
 use thiserror::Error;
 
@@ -11,36 +13,44 @@ pub enum Error {
     MissingDelimiter,
 }
 
-type Id = u64;
-type Ranges = Vec<RangeInclusive<Id>>;
+pub type Id = u64;
+pub type Ranges = Vec<RangeInclusive<Id>>;
+
+/// Prefix marking a comment line in the input.
+pub const COMMENT: &str = "#";
 
 pub fn solve(input: &str) -> Result<usize, Error> {
-    let (ranges, ids) = input.split_once("\n\n").ok_or(Error::MissingBlankLine)?;
-    let ranges = parse_ranges(ranges)?;
+    let (ranges, ids) = split_sections(input)?;
+    let ranges: IntervalSet = parse_ranges(ranges)?.into_iter().collect();
 
-    ids.lines()
-        .map(|line| {
-            line.parse::<Id>().map_err(Error::from).map(|id| {
-                if ranges.iter().any(|range| range.contains(&id)) {
-                    1usize
-                } else {
-                    0usize
-                }
-            })
-        })
+    ids.into_iter()
+        .map(|line| parse_id(line).map(|id| if ranges.contains(id) { 1usize } else { 0usize }))
         .sum()
 }
 
-fn parse_ranges(input: &str) -> Result<Ranges, Error> {
-    input
-        .lines()
-        .map(|line| {
-            let (start, end) = line.split_once('-').ok_or(Error::MissingDelimiter)?;
-            let start: Id = start.parse()?;
-            let end: Id = end.parse()?;
-            Ok(start..=end)
-        })
-        .collect()
+/// Split the input into the fresh ranges section and the available IDs section.
+/// The IDs section may be missing, and any further sections are treated as
+/// more IDs.
+pub fn split_sections(input: &str) -> Result<(Vec<&str>, Vec<&str>), Error> {
+    let mut sections = sections(input).with_comments(COMMENT);
+    let ranges = sections.next().ok_or(Error::MissingBlankLine)?;
+    Ok((ranges, sections.flatten().collect()))
+}
+
+pub fn parse_ranges<'a>(lines: impl IntoIterator<Item = &'a str>) -> Result<Ranges, Error> {
+    lines.into_iter().map(parse_range).collect()
+}
+
+/// Parse a range such as `3-5`, allowing spaces around the numbers.
+pub fn parse_range(line: &str) -> Result<RangeInclusive<Id>, Error> {
+    let (start, end) = line.split_once('-').ok_or(Error::MissingDelimiter)?;
+    let start: Id = start.trim().parse()?;
+    let end: Id = end.trim().parse()?;
+    Ok(start..=end)
+}
+
+pub fn parse_id(line: &str) -> Result<Id, Error> {
+    Ok(line.trim().parse()?)
 }
 
 #[cfg(test)]
@@ -55,6 +65,14 @@ mod tests {
         assert_eq!(result, 3);
     }
 
+    #[test]
+    fn example_with_crlf_and_comments() {
+        let input = "\r\n# fresh ranges\r\n3 - 5\r\n10-14\r\n16 -20\r\n12- 18\r\n\r\n\r\n\
+                     # ids\r\n1\r\n5\r\n8\r\n11\r\n17\r\n32\r\n";
+        let result = solve(input).unwrap();
+        assert_eq!(result, 3);
+    }
+
     #[cfg(input_txt)]
     #[cfg(part1_txt)]
     #[test]
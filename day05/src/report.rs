@@ -0,0 +1,169 @@
+use crate::{
+    interval_set::IntervalSet,
+    part1::{self, Error, Id, Ranges},
+};
+use std::{fmt::Write, ops::RangeInclusive};
+
+/// Audit of which input ranges cover each available ID.
+#[derive(Debug)]
+pub struct Report {
+    pub ranges: Ranges,
+    /// Number of IDs that fall in each range, indexed like `ranges`.
+    pub hits: Vec<usize>,
+    pub ids: Vec<IdReport>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct IdReport {
+    pub id: Id,
+    /// Indices into `Report::ranges` of every range containing the ID.
+    pub covering: Vec<usize>,
+    /// The merged interval containing the ID, if it is fresh.
+    pub merged: Option<RangeInclusive<Id>>,
+}
+
+impl IdReport {
+    pub fn is_fresh(&self) -> bool {
+        self.merged.is_some()
+    }
+}
+
+pub fn build(input: &str) -> Result<Report, Error> {
+    let (ranges, ids) = part1::split_sections(input)?;
+    let ranges = part1::parse_ranges(ranges)?;
+    let merged: IntervalSet = ranges.iter().cloned().collect();
+
+    let mut by_start = (0..ranges.len()).collect::<Vec<_>>();
+    by_start.sort_unstable_by_key(|&i| *ranges[i].start());
+
+    let mut hits = vec![0; ranges.len()];
+    let ids = ids
+        .into_iter()
+        .map(|line| {
+            let id = part1::parse_id(line)?;
+            let candidates = by_start.partition_point(|&i| *ranges[i].start() <= id);
+            let mut covering = by_start[..candidates]
+                .iter()
+                .copied()
+                .filter(|&i| *ranges[i].end() >= id)
+                .collect::<Vec<_>>();
+            covering.sort_unstable();
+            for &i in &covering {
+                hits[i] += 1;
+            }
+            Ok(IdReport {
+                id,
+                covering,
+                merged: merged.get(id).cloned(),
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    Ok(Report { ranges, hits, ids })
+}
+
+impl Report {
+    /// One row per ID, with the covering ranges separated by `;`.
+    pub fn ids_csv(&self) -> String {
+        let mut csv = String::from("id,fresh,merged_start,merged_end,covering\n");
+        for report in &self.ids {
+            let (start, end) = match &report.merged {
+                Some(range) => (range.start().to_string(), range.end().to_string()),
+                None => (String::new(), String::new()),
+            };
+            let covering = report
+                .covering
+                .iter()
+                .map(|&i| format!("{}-{}", self.ranges[i].start(), self.ranges[i].end()))
+                .collect::<Vec<_>>()
+                .join(";");
+            writeln!(
+                csv,
+                "{},{},{start},{end},{covering}",
+                report.id,
+                report.is_fresh()
+            )
+            .unwrap();
+        }
+        csv
+    }
+
+    /// One row per input range with its hit count.
+    pub fn ranges_csv(&self) -> String {
+        let mut csv = String::from("index,start,end,hits\n");
+        for (i, (range, hits)) in self.ranges.iter().zip(&self.hits).enumerate() {
+            writeln!(csv, "{i},{},{},{hits}", range.start(), range.end()).unwrap();
+        }
+        csv
+    }
+
+    pub fn to_json(&self) -> String {
+        let ranges = self
+            .ranges
+            .iter()
+            .zip(&self.hits)
+            .enumerate()
+            .map(|(i, (range, hits))| {
+                format!(
+                    r#"{{"index":{i},"start":{},"end":{},"hits":{hits}}}"#,
+                    range.start(),
+                    range.end()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        let ids = self
+            .ids
+            .iter()
+            .map(|report| {
+                let merged = match &report.merged {
+                    Some(range) => format!("[{},{}]", range.start(), range.end()),
+                    None => "null".to_owned(),
+                };
+                let covering = report
+                    .covering
+                    .iter()
+                    .map(usize::to_string)
+                    .collect::<Vec<_>>()
+                    .join(",");
+                format!(
+                    r#"{{"id":{},"fresh":{},"merged":{merged},"covering":[{covering}]}}"#,
+                    report.id,
+                    report.is_fresh()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(r#"{{"ranges":[{ranges}],"ids":[{ids}]}}"#)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn example() {
+        let report = build(EXAMPLE).unwrap();
+        assert_eq!(report.hits, [1, 1, 1, 1]);
+        assert_eq!(
+            report.ids[4],
+            IdReport {
+                id: 17,
+                covering: vec![2, 3],
+                merged: Some(10..=20),
+            }
+        );
+        assert_eq!(report.ids.iter().filter(|r| r.is_fresh()).count(), 3);
+    }
+
+    #[test]
+    fn example_csv() {
+        let report = build(EXAMPLE).unwrap();
+        let csv = report.ids_csv();
+        assert_eq!(csv.lines().nth(5), Some("17,true,10,20,16-20;12-18"));
+        assert_eq!(csv.lines().nth(1), Some("1,false,,,"));
+    }
+}
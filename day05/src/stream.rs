@@ -0,0 +1,166 @@
+use crate::{
+    interval_set::IntervalSet,
+    part1::{self, Id},
+};
+use common::sections::{Line, classify};
+use std::{fmt, io::BufRead};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Error reading input: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    InputError(#[from] part1::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Fresh,
+    Spoiled,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Fresh => write!(f, "fresh"),
+            Verdict::Spoiled => write!(f, "spoiled"),
+        }
+    }
+}
+
+/// Running totals of the verdicts seen so far.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Tally {
+    pub fresh: usize,
+    pub spoiled: usize,
+}
+
+impl Tally {
+    pub fn add(&mut self, verdict: Verdict) {
+        match verdict {
+            Verdict::Fresh => self.fresh += 1,
+            Verdict::Spoiled => self.spoiled += 1,
+        }
+    }
+}
+
+/// Checks IDs against the fresh ranges without needing the full list of IDs up front.
+pub struct Checker {
+    ranges: IntervalSet,
+}
+
+impl Checker {
+    pub fn new(ranges: IntervalSet) -> Self {
+        Self { ranges }
+    }
+
+    /// Read the fresh ranges section from the start of `reader`, consuming the
+    /// blank line that follows it so the remaining lines are the IDs.
+    pub fn from_reader(reader: &mut impl BufRead) -> Result<Self, Error> {
+        let mut ranges = IntervalSet::new();
+        let mut in_section = false;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                return Err(part1::Error::MissingBlankLine.into());
+            }
+            match classify(&line, Some(part1::COMMENT)) {
+                Line::Content(range) => {
+                    ranges.insert(part1::parse_range(range)?);
+                    in_section = true;
+                }
+                Line::Blank if in_section => break,
+                Line::Blank | Line::Comment => {}
+            }
+        }
+        Ok(Self::new(ranges))
+    }
+
+    pub fn check(&self, id: Id) -> Verdict {
+        if self.ranges.contains(id) {
+            Verdict::Fresh
+        } else {
+            Verdict::Spoiled
+        }
+    }
+
+    /// Check each line of `reader` as an ID, one line at a time.
+    pub fn check_lines(
+        &self,
+        reader: impl BufRead,
+    ) -> impl Iterator<Item = Result<(Id, Verdict), Error>> {
+        reader
+            .lines()
+            .filter_map(|line| match line {
+                Ok(line) => match classify(&line, Some(part1::COMMENT)) {
+                    Line::Content(id) => Some(part1::parse_id(id).map_err(Error::from)),
+                    Line::Blank | Line::Comment => None,
+                },
+                Err(error) => Some(Err(error.into())),
+            })
+            .map(|id| id.map(|id| (id, self.check(id))))
+    }
+
+    /// Count the fresh and spoiled IDs in `reader`.
+    pub fn tally(&self, reader: impl BufRead) -> Result<Tally, Error> {
+        self.check_lines(reader)
+            .try_fold(Tally::default(), |mut tally, result| {
+                let (_, verdict) = result?;
+                tally.add(verdict);
+                Ok(tally)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn example() {
+        let mut reader = EXAMPLE.as_bytes();
+        let checker = Checker::from_reader(&mut reader).unwrap();
+        let verdicts = checker
+            .check_lines(reader)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            verdicts,
+            [
+                (1, Verdict::Spoiled),
+                (5, Verdict::Fresh),
+                (8, Verdict::Spoiled),
+                (11, Verdict::Fresh),
+                (17, Verdict::Fresh),
+                (32, Verdict::Spoiled),
+            ]
+        );
+    }
+
+    #[test]
+    fn example_with_crlf() {
+        let input = EXAMPLE.replace('\n', "\r\n");
+        let mut reader = input.as_bytes();
+        let checker = Checker::from_reader(&mut reader).unwrap();
+        let tally = checker.tally(reader).unwrap();
+        assert_eq!(tally.fresh, 3);
+    }
+
+    #[test]
+    fn example_tally() {
+        let mut reader = EXAMPLE.as_bytes();
+        let checker = Checker::from_reader(&mut reader).unwrap();
+        let tally = checker.tally(reader).unwrap();
+        assert_eq!(
+            tally,
+            Tally {
+                fresh: 3,
+                spoiled: 3
+            }
+        );
+    }
+}
@@ -0,0 +1,25 @@
+use day06::{
+    INPUT,
+    worksheet::{Reading, Worksheet},
+};
+
+fn main() {
+    println!("{:<60} {:>20} {:>20}", "reading", "rows", "columns");
+    for reading in Reading::all() {
+        let (rows, columns) = match Worksheet::parse_with(INPUT, reading) {
+            Ok(worksheet) => (
+                show(worksheet.total_rowwise()),
+                show(worksheet.total_columnwise()),
+            ),
+            Err(error) => (error.to_string(), String::new()),
+        };
+        println!("{:<60} {rows:>20} {columns:>20}", reading.to_string());
+    }
+}
+
+fn show(result: Result<u128, day06::worksheet::Error>) -> String {
+    match result {
+        Ok(value) => value.to_string(),
+        Err(error) => error.to_string(),
+    }
+}
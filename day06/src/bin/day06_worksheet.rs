@@ -0,0 +1,31 @@
+use day06::{
+    INPUT,
+    worksheet::{Columnwise, Error, Worksheet},
+};
+
+fn main() -> Result<(), Error> {
+    let worksheet: Worksheet = INPUT.parse()?;
+    for (index, problem) in worksheet.problems.iter().enumerate() {
+        println!(
+            "Problem {index} (columns {}..{}):",
+            problem.column_span.start, problem.column_span.end
+        );
+        println!(
+            "  rows:    {problem} = {}",
+            show(problem.evaluate_rowwise::<u128>())
+        );
+        println!(
+            "  columns: {} = {}",
+            Columnwise(problem),
+            show(problem.evaluate_columnwise::<u128>())
+        );
+    }
+    Ok(())
+}
+
+fn show(result: Result<u128, Error>) -> String {
+    match result {
+        Ok(value) => value.to_string(),
+        Err(error) => error.to_string(),
+    }
+}
@@ -1,5 +1,6 @@
 pub mod part1;
 pub mod part2;
+pub mod worksheet;
 
 #[cfg(input_txt)]
 pub const INPUT: &str = include_str!("../input.txt");
@@ -1,33 +1,16 @@
-use thiserror::Error;
-
-#[derive(Debug, Error)]
-pub enum Error {
-    #[error("Error parsing input: {0}")]
-    ParsingError(#[from] std::num::ParseIntError),
-    #[error("Invalid operator: {0}")]
-    InvalidOperator(String),
-    #[error("No operators found in input")]
-    NoOperators,
-}
+use crate::worksheet::Worksheet;
+
+pub use crate::worksheet::Error;
 
 pub fn solve(input: &str) -> Result<u64, Error> {
-    let mut rows = input
-        .lines()
-        .map(&str::split_ascii_whitespace)
-        .collect::<Vec<_>>();
-    let ops = rows.pop().ok_or(Error::NoOperators)?;
-    ops.map(|op| {
-        let numbers = rows
-            .iter_mut()
-            .filter_map(|row| row.next())
-            .map(|s| s.parse::<u64>().map_err(Error::from));
-        match op {
-            "+" => numbers.sum::<Result<u64, Error>>(),
-            "*" => numbers.product::<Result<u64, Error>>(),
-            _ => Err(Error::InvalidOperator(op.to_owned())),
-        }
-    })
-    .sum()
+    let worksheet: Worksheet = input.parse()?;
+    worksheet.total_rowwise()
+}
+
+/// Same as `solve` but evaluates in 128 bits for worksheets that overflow `u64`.
+pub fn solve_u128(input: &str) -> Result<u128, Error> {
+    let worksheet: Worksheet = input.parse()?;
+    worksheet.total_rowwise()
 }
 
 #[cfg(test)]
@@ -42,6 +25,12 @@ mod tests {
         assert_eq!(result, 4277556);
     }
 
+    #[test]
+    fn example_u128() {
+        let result = solve_u128(EXAMPLE).unwrap();
+        assert_eq!(result, 4277556);
+    }
+
     #[cfg(input_txt)]
     #[cfg(part1_txt)]
     #[test]
@@ -1,48 +1,28 @@
-use thiserror::Error;
-
-#[derive(Debug, Error)]
-pub enum Error {
-    #[error("Error parsing input: {0}")]
-    ParsingError(#[from] std::num::ParseIntError),
-    #[error("Invalid operator: {0}")]
-    InvalidOperator(u8),
-    #[error("No operators found in input")]
-    NoOperators,
-}
+use crate::worksheet::{Reading, Worksheet};
+
+pub use crate::worksheet::Error;
 
 pub fn solve(input: &str) -> Result<u64, Error> {
-    let mut lines = input.lines().map(&str::as_bytes).collect::<Vec<_>>();
-    lines
-        .pop()
-        .ok_or(Error::NoOperators)?
-        .iter()
-        .enumerate()
-        .filter(|(_, ch)| !ch.is_ascii_whitespace())
-        .map(|(index, op)| {
-            let numbers = (index..).map_while(|index| {
-                lines
-                    .iter()
-                    .filter_map(|line| line.get(index))
-                    .fold(None, |acc, &ch| {
-                        if ch.is_ascii_digit() {
-                            Some(acc.unwrap_or(0) * 10 + (ch - b'0') as u64)
-                        } else {
-                            acc
-                        }
-                    })
-            });
-            match op {
-                b'+' => Ok(numbers.sum::<u64>()),
-                b'*' => Ok(numbers.product()),
-                _ => Err(Error::InvalidOperator(*op)),
-            }
-        })
-        .sum()
+    let worksheet: Worksheet = input.parse()?;
+    worksheet.total_columnwise()
+}
+
+/// Same as `solve` but evaluates in 128 bits for worksheets that overflow `u64`.
+pub fn solve_u128(input: &str) -> Result<u128, Error> {
+    let worksheet: Worksheet = input.parse()?;
+    worksheet.total_columnwise()
+}
+
+/// Same as `solve` but with the columns read as described by `reading`.
+pub fn solve_with(input: &str, reading: Reading) -> Result<u64, Error> {
+    let worksheet = Worksheet::parse_with(input, reading)?;
+    worksheet.total_columnwise()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::worksheet::Direction;
 
     const EXAMPLE: &str = include_str!("../example.txt");
 
@@ -52,6 +32,22 @@ mod tests {
         assert_eq!(result, 3263827);
     }
 
+    #[test]
+    fn example_u128() {
+        let result = solve_u128(EXAMPLE).unwrap();
+        assert_eq!(result, 3263827);
+    }
+
+    #[test]
+    fn example_right_to_left() {
+        let reading = Reading {
+            direction: Direction::RightToLeft,
+            ..Reading::default()
+        };
+        let result = solve_with(EXAMPLE, reading).unwrap();
+        assert_eq!(result, 3263827);
+    }
+
     #[cfg(input_txt)]
     #[cfg(part2_txt)]
     #[test]
@@ -0,0 +1,464 @@
+use std::{fmt, ops::Range, str::FromStr};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Error parsing input: {0}")]
+    ParsingError(#[from] std::num::ParseIntError),
+    #[error("Invalid operator: {0}")]
+    InvalidOperator(String),
+    #[error("No operators found in input")]
+    NoOperators,
+    #[error("Arithmetic overflow in problem at column {0}")]
+    Overflow(usize),
+    #[error("Division by zero in problem at column {0}")]
+    DivisionByZero(usize),
+    #[error("No numbers in problem at column {0}")]
+    EmptyProblem(usize),
+    #[error("Arithmetic overflow adding up the problems")]
+    TotalOverflow,
+}
+
+/// Integer types that problems can be evaluated in.
+pub trait Number: Copy + Ord + fmt::Display + From<u64> {
+    const ZERO: Self;
+    const ONE: Self;
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! impl_number {
+    ($($t:ty),*) => {
+        $(impl Number for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_sub(self, rhs)
+            }
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_mul(self, rhs)
+            }
+            fn checked_div(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_div(self, rhs)
+            }
+        })*
+    };
+}
+
+impl_number!(u64, u128);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Max,
+    Min,
+}
+
+impl Operator {
+    /// Apply the operator to the numbers in order, so `-` and `/` take the
+    /// first number and subtract or divide by each of the rest. `column` is
+    /// only used to identify the problem in errors.
+    pub fn apply<N: Number>(self, numbers: &[u64], column: usize) -> Result<N, Error> {
+        let mut numbers = numbers.iter().map(|&n| N::from(n));
+        let overflow = || Error::Overflow(column);
+        match self {
+            Operator::Add => {
+                numbers.try_fold(N::ZERO, |acc, n| acc.checked_add(n).ok_or_else(overflow))
+            }
+            Operator::Multiply => {
+                numbers.try_fold(N::ONE, |acc, n| acc.checked_mul(n).ok_or_else(overflow))
+            }
+            Operator::Subtract => {
+                let first = numbers.next().ok_or(Error::EmptyProblem(column))?;
+                numbers.try_fold(first, |acc, n| acc.checked_sub(n).ok_or_else(overflow))
+            }
+            Operator::Divide => {
+                let first = numbers.next().ok_or(Error::EmptyProblem(column))?;
+                numbers.try_fold(first, |acc, n| {
+                    acc.checked_div(n).ok_or(Error::DivisionByZero(column))
+                })
+            }
+            Operator::Max => numbers.max().ok_or(Error::EmptyProblem(column)),
+            Operator::Min => numbers.min().ok_or(Error::EmptyProblem(column)),
+        }
+    }
+}
+
+impl FromStr for Operator {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "+" => Ok(Operator::Add),
+            "-" => Ok(Operator::Subtract),
+            "*" => Ok(Operator::Multiply),
+            "/" => Ok(Operator::Divide),
+            "max" => Ok(Operator::Max),
+            "min" => Ok(Operator::Min),
+            _ => Err(Error::InvalidOperator(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operator::Add => write!(f, "+"),
+            Operator::Subtract => write!(f, "-"),
+            Operator::Multiply => write!(f, "*"),
+            Operator::Divide => write!(f, "/"),
+            Operator::Max => write!(f, "max"),
+            Operator::Min => write!(f, "min"),
+        }
+    }
+}
+
+/// Order in which the columns of a problem are read.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Direction {
+    #[default]
+    LeftToRight,
+    RightToLeft,
+}
+
+/// Which end of a column holds the most significant digit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Significance {
+    #[default]
+    TopFirst,
+    BottomFirst,
+}
+
+/// Where the row of operators sits in the worksheet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OperatorRow {
+    #[default]
+    Bottom,
+    Top,
+}
+
+/// How to interpret the layout of the worksheet when reading it column-wise.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Reading {
+    pub direction: Direction,
+    pub significance: Significance,
+    pub operator_row: OperatorRow,
+}
+
+impl Reading {
+    /// Every combination of reading options.
+    pub fn all() -> impl Iterator<Item = Reading> {
+        [OperatorRow::Bottom, OperatorRow::Top]
+            .into_iter()
+            .flat_map(|operator_row| {
+                [Significance::TopFirst, Significance::BottomFirst]
+                    .into_iter()
+                    .map(move |significance| (operator_row, significance))
+            })
+            .flat_map(|(operator_row, significance)| {
+                [Direction::LeftToRight, Direction::RightToLeft]
+                    .into_iter()
+                    .map(move |direction| Reading {
+                        direction,
+                        significance,
+                        operator_row,
+                    })
+            })
+    }
+}
+
+impl fmt::Display for Reading {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let direction = match self.direction {
+            Direction::LeftToRight => "left to right",
+            Direction::RightToLeft => "right to left",
+        };
+        let significance = match self.significance {
+            Significance::TopFirst => "top digit first",
+            Significance::BottomFirst => "bottom digit first",
+        };
+        let operator_row = match self.operator_row {
+            OperatorRow::Bottom => "operators at bottom",
+            OperatorRow::Top => "operators at top",
+        };
+        write!(f, "{direction}, {significance}, {operator_row}")
+    }
+}
+
+/// A single problem from the worksheet, with its numbers read both ways.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub op: Operator,
+    /// Numbers read across each row, top to bottom.
+    pub numbers_rowwise: Vec<u64>,
+    /// Numbers read down each column, in the order given by the `Reading`.
+    pub numbers_columnwise: Vec<u64>,
+    /// Byte columns of the input covered by this problem.
+    pub column_span: Range<usize>,
+}
+
+impl Problem {
+    pub fn evaluate_rowwise<N: Number>(&self) -> Result<N, Error> {
+        self.op.apply(&self.numbers_rowwise, self.column_span.start)
+    }
+
+    pub fn evaluate_columnwise<N: Number>(&self) -> Result<N, Error> {
+        self.op
+            .apply(&self.numbers_columnwise, self.column_span.start)
+    }
+}
+
+impl fmt::Display for Problem {
+    /// Writes the row-wise reading, e.g. `123 * 45 * 6`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_expression(f, self.op, &self.numbers_rowwise)
+    }
+}
+
+/// Helper to display the column-wise reading of a problem.
+pub struct Columnwise<'a>(pub &'a Problem);
+
+impl fmt::Display for Columnwise<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_expression(f, self.0.op, &self.0.numbers_columnwise)
+    }
+}
+
+fn write_expression(f: &mut fmt::Formatter<'_>, op: Operator, numbers: &[u64]) -> fmt::Result {
+    for (i, number) in numbers.iter().enumerate() {
+        if i > 0 {
+            write!(f, " {op} ")?;
+        }
+        write!(f, "{number}")?;
+    }
+    Ok(())
+}
+
+/// The column-aligned worksheet: rows of numbers with a row of operators
+/// underneath, where each operator marks the first column of its problem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Worksheet {
+    pub problems: Vec<Problem>,
+}
+
+impl FromStr for Worksheet {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(input, Reading::default())
+    }
+}
+
+impl Worksheet {
+    /// Parse the worksheet, reading the columns of each problem as described by `reading`.
+    pub fn parse_with(input: &str, reading: Reading) -> Result<Self, Error> {
+        let mut rows = input.lines().map(&str::as_bytes).collect::<Vec<_>>();
+        let ops = match reading.operator_row {
+            OperatorRow::Bottom => rows.pop(),
+            OperatorRow::Top if !rows.is_empty() => Some(rows.remove(0)),
+            OperatorRow::Top => None,
+        }
+        .ok_or(Error::NoOperators)?;
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+
+        let starts = ops
+            .iter()
+            .enumerate()
+            .filter(|&(i, ch)| {
+                !ch.is_ascii_whitespace() && (i == 0 || ops[i - 1].is_ascii_whitespace())
+            })
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        if starts.is_empty() {
+            return Err(Error::NoOperators);
+        }
+
+        let problems = starts
+            .iter()
+            .enumerate()
+            .map(|(n, &start)| {
+                let end = starts.get(n + 1).map_or(width.max(ops.len()), |&next| next);
+                let token = token_at(ops, start);
+                let op = token.parse()?;
+                let column_span = start..end;
+                let numbers_rowwise = rows
+                    .iter()
+                    .filter_map(|row| {
+                        let text = row.get(start..end.min(row.len()))?;
+                        let text = std::str::from_utf8(text).ok()?.trim();
+                        (!text.is_empty()).then(|| text.parse::<u64>())
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let mut columns = column_span.clone().collect::<Vec<_>>();
+                if reading.direction == Direction::RightToLeft {
+                    columns.reverse();
+                }
+                let numbers_columnwise = columns
+                    .into_iter()
+                    .filter_map(|column| {
+                        read_column(&rows, column, start, reading.significance).transpose()
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Problem {
+                    op,
+                    numbers_rowwise,
+                    numbers_columnwise,
+                    column_span,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(Worksheet { problems })
+    }
+
+    /// Sum of every problem read row-wise, failing if any step overflows `N`.
+    pub fn total_rowwise<N: Number>(&self) -> Result<N, Error> {
+        self.total(Problem::evaluate_rowwise)
+    }
+
+    /// Sum of every problem read column-wise, failing if any step overflows `N`.
+    pub fn total_columnwise<N: Number>(&self) -> Result<N, Error> {
+        self.total(Problem::evaluate_columnwise)
+    }
+
+    fn total<N: Number>(&self, evaluate: fn(&Problem) -> Result<N, Error>) -> Result<N, Error> {
+        self.problems.iter().try_fold(N::ZERO, |acc, problem| {
+            acc.checked_add(evaluate(problem)?)
+                .ok_or(Error::TotalOverflow)
+        })
+    }
+}
+
+/// The operator token starting at `start`, as text.
+fn token_at(ops: &[u8], start: usize) -> String {
+    let token = ops[start..]
+        .iter()
+        .take_while(|ch| !ch.is_ascii_whitespace())
+        .copied()
+        .collect::<Vec<_>>();
+    String::from_utf8_lossy(&token).into_owned()
+}
+
+/// Read the digits in a column as a single number, most significant digit first.
+/// Overflow is reported at `problem`, the column where the problem starts.
+fn read_column(
+    rows: &[&[u8]],
+    column: usize,
+    problem: usize,
+    significance: Significance,
+) -> Result<Option<u64>, Error> {
+    let mut digits = rows
+        .iter()
+        .filter_map(|row| row.get(column))
+        .filter(|ch| ch.is_ascii_digit())
+        .map(|ch| (ch - b'0') as u64)
+        .collect::<Vec<_>>();
+    if digits.is_empty() {
+        return Ok(None);
+    }
+    if significance == Significance::BottomFirst {
+        digits.reverse();
+    }
+    digits
+        .into_iter()
+        .try_fold(0u64, |acc, digit| acc.checked_mul(10)?.checked_add(digit))
+        .map(Some)
+        .ok_or(Error::Overflow(problem))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn example() {
+        let worksheet: Worksheet = EXAMPLE.parse().unwrap();
+        assert_eq!(worksheet.problems.len(), 4);
+        assert_eq!(
+            worksheet.problems[0],
+            Problem {
+                op: Operator::Multiply,
+                numbers_rowwise: vec![123, 45, 6],
+                numbers_columnwise: vec![1, 24, 356],
+                column_span: 0..4,
+            }
+        );
+        assert_eq!(worksheet.problems[3].to_string(), "64 + 23 + 314");
+        assert_eq!(
+            Columnwise(&worksheet.problems[3]).to_string(),
+            "623 + 431 + 4"
+        );
+    }
+
+    #[test]
+    fn reading_options() {
+        let reading = Reading {
+            direction: Direction::RightToLeft,
+            significance: Significance::BottomFirst,
+            operator_row: OperatorRow::Bottom,
+        };
+        let worksheet = Worksheet::parse_with(EXAMPLE, reading).unwrap();
+        assert_eq!(worksheet.problems[0].numbers_columnwise, [653, 42, 1]);
+
+        let flipped = EXAMPLE.lines().rev().collect::<Vec<_>>().join("\n");
+        let reading = Reading {
+            operator_row: OperatorRow::Top,
+            ..Reading::default()
+        };
+        let worksheet = Worksheet::parse_with(&flipped, reading).unwrap();
+        assert_eq!(worksheet.problems[0].numbers_rowwise, [6, 45, 123]);
+        assert_eq!(worksheet.problems[0].numbers_columnwise, [1, 42, 653]);
+        assert_eq!(Reading::all().count(), 8);
+    }
+
+    #[test]
+    fn other_operators() {
+        let worksheet: Worksheet = "100 7 3   9\n 20 2 5   4\n  5 0 4   6\n-   / max min"
+            .parse()
+            .unwrap();
+        let results = worksheet
+            .problems
+            .iter()
+            .map(|problem| problem.evaluate_rowwise::<u64>())
+            .collect::<Vec<_>>();
+        assert_eq!(results[0].as_ref().unwrap(), &75);
+        assert!(matches!(results[1], Err(Error::DivisionByZero(4))));
+        assert_eq!(results[2].as_ref().unwrap(), &5);
+        assert_eq!(results[3].as_ref().unwrap(), &4);
+    }
+
+    #[test]
+    fn overflow() {
+        let input = "4294967296 1\n4294967296 2\n*          +";
+        let worksheet: Worksheet = input.parse().unwrap();
+        assert!(matches!(
+            worksheet.total_rowwise::<u64>(),
+            Err(Error::Overflow(0))
+        ));
+        assert_eq!(
+            worksheet.total_rowwise::<u128>().unwrap(),
+            18446744073709551616 + 3
+        );
+    }
+
+    #[test]
+    fn column_overflow_points_at_problem() {
+        // Column 3 holds 21 digits, in the problem starting at column 2.
+        let rows = vec!["  12"; 20].join("\n");
+        let input = format!("1 12\n{rows}\n+ * ");
+        assert!(matches!(
+            input.parse::<Worksheet>(),
+            Err(Error::Overflow(2))
+        ));
+    }
+}
@@ -1,6 +1,27 @@
-use day07::{part2, INPUT};
+//! Usage: `day07_part2 [--modulo M]`
+//!
+//! Prints the number of timelines, or the number modulo M with `--modulo`.
 
-fn main() -> Result<(), part2::Error> {
-    println!("{:?}", part2::solve(INPUT)?);
+use day07::{INPUT, manifold::Edge, part2};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut modulus = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--modulo" => {
+                let value = args.next().ok_or("--modulo needs a value")?;
+                modulus = Some(value.parse::<u64>()?);
+            }
+            _ => return Err(format!("Unknown argument: {arg}").into()),
+        }
+    }
+    match modulus {
+        Some(modulus) => println!(
+            "{:?}",
+            part2::solve_modulo(INPUT, Edge::default(), modulus)?
+        ),
+        None => println!("{:?}", part2::solve(INPUT)?),
+    }
     Ok(())
 }
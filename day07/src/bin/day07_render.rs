@@ -0,0 +1,28 @@
+//! Usage: `day07_render [OUTPUT]`
+//!
+//! Draws the beams through the manifold, writing to OUTPUT if given or to the
+//! terminal otherwise. Hit splitters are shown as `^` and unused ones as `x`.
+
+use day07::{
+    INPUT,
+    manifold::{Edge, Manifold},
+};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let manifold: Manifold = INPUT.parse()?;
+    let simulation = manifold.simulate(Edge::default());
+    match std::env::args().nth(1) {
+        Some(path) => std::fs::write(path, simulation.to_string())?,
+        None => print!("{simulation}"),
+    }
+    let unused = simulation.unused_splitters().collect::<Vec<_>>();
+    eprintln!(
+        "{} splits, {} unused splitters",
+        simulation.splits,
+        unused.len()
+    );
+    for (row, column) in unused {
+        eprintln!("  unused splitter at row {row}, column {column}");
+    }
+    Ok(())
+}
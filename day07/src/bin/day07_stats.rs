@@ -0,0 +1,15 @@
+//! Usage: `day07_stats [--exits]`
+//!
+//! Prints a CSV of the timelines reaching each splitter, or of the timelines
+//! leaving each column of the bottom row with `--exits`.
+
+use day07::{INPUT, part2};
+
+fn main() -> Result<(), part2::Error> {
+    let stats = part2::statistics(INPUT)?;
+    match std::env::args().nth(1).as_deref() {
+        Some("--exits") => print!("{}", stats.exits_csv()),
+        _ => print!("{}", stats.splitters_csv()),
+    }
+    Ok(())
+}
@@ -0,0 +1,327 @@
+use std::{fmt, str::FromStr};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Input is empty")]
+    EmptyInput,
+    #[error("Start marker 'S' not found in input")]
+    StartMarkerNotFound,
+}
+
+/// An element of the manifold that a beam can pass through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Element {
+    Empty,
+    Start,
+    /// `^` splits the beam into the columns either side.
+    Splitter,
+    /// `/` shifts the beam one column to the left.
+    MirrorLeft,
+    /// `\` shifts the beam one column to the right.
+    MirrorRight,
+    /// `#` stops the beam.
+    Absorber,
+}
+
+impl From<u8> for Element {
+    fn from(c: u8) -> Self {
+        match c {
+            b'S' => Element::Start,
+            b'^' => Element::Splitter,
+            b'/' => Element::MirrorLeft,
+            b'\\' => Element::MirrorRight,
+            b'#' => Element::Absorber,
+            _ => Element::Empty,
+        }
+    }
+}
+
+impl Element {
+    /// The element at `column` of `line`, treating anything past the end as empty.
+    pub fn at(line: &[u8], column: usize) -> Self {
+        line.get(column).map_or(Element::Empty, |&c| c.into())
+    }
+
+    /// Columns the beam in `column` continues in after passing this element,
+    /// with beams leaving the sides of the manifold handled according to `edge`.
+    pub fn targets(self, column: usize, width: usize, edge: Edge) -> impl Iterator<Item = usize> {
+        let offsets = match self {
+            Element::Empty | Element::Start => [Some(0), None],
+            Element::Splitter => [Some(-1), Some(1)],
+            Element::MirrorLeft => [Some(-1), None],
+            Element::MirrorRight => [Some(1), None],
+            Element::Absorber => [None, None],
+        };
+        offsets
+            .into_iter()
+            .flatten()
+            .filter_map(move |offset| edge.apply(column as isize + offset, width))
+    }
+}
+
+/// What happens to a beam that is pushed off the side of the manifold.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Edge {
+    /// The beam leaves the manifold.
+    #[default]
+    Drop,
+    /// The beam stays in the outermost column.
+    Clamp,
+    /// The beam re-enters from the opposite side.
+    Wrap,
+}
+
+impl Edge {
+    fn apply(self, column: isize, width: usize) -> Option<usize> {
+        let width = width as isize;
+        if width == 0 {
+            None
+        } else if (0..width).contains(&column) {
+            Some(column as usize)
+        } else {
+            match self {
+                Edge::Drop => None,
+                Edge::Clamp => Some(column.clamp(0, width - 1) as usize),
+                Edge::Wrap => Some(column.rem_euclid(width) as usize),
+            }
+        }
+    }
+}
+
+/// A cell of the annotated manifold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    Empty,
+    Start,
+    Beam,
+    SplitterHit,
+    SplitterUnused,
+    MirrorLeft,
+    MirrorRight,
+    Absorber,
+}
+
+impl Cell {
+    pub fn symbol(self) -> char {
+        match self {
+            Cell::Empty => '.',
+            Cell::Start => 'S',
+            Cell::Beam => '|',
+            Cell::SplitterHit => '^',
+            Cell::SplitterUnused => 'x',
+            Cell::MirrorLeft => '/',
+            Cell::MirrorRight => '\\',
+            Cell::Absorber => '#',
+        }
+    }
+}
+
+/// The tachyon manifold as read from the input.
+#[derive(Debug, Clone)]
+pub struct Manifold {
+    rows: Vec<Vec<u8>>,
+    start: usize,
+}
+
+impl FromStr for Manifold {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let rows = input
+            .lines()
+            .map(|line| line.as_bytes().to_vec())
+            .collect::<Vec<_>>();
+        let start = rows
+            .first()
+            .ok_or(Error::EmptyInput)?
+            .iter()
+            .position(|&c| c == b'S')
+            .ok_or(Error::StartMarkerNotFound)?;
+        Ok(Manifold { rows, start })
+    }
+}
+
+impl Manifold {
+    pub fn width(&self) -> usize {
+        self.rows[0].len()
+    }
+
+    /// Number of times a beam is split on its way to the bottom of the manifold.
+    pub fn splits(&self, edge: Edge) -> usize {
+        let mut splits = 0;
+        self.trace_beams(edge, |_, line, arriving, _| {
+            splits += hit_splitters(line, arriving).count();
+        });
+        splits
+    }
+
+    /// Trace the beams from the start marker to the bottom of the manifold,
+    /// drawing each beam and marking every splitter as hit or unused.
+    pub fn simulate(&self, edge: Edge) -> Simulation {
+        let mut cells = self
+            .rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&c| match Element::from(c) {
+                        Element::Empty => Cell::Empty,
+                        Element::Start => Cell::Start,
+                        Element::Splitter => Cell::SplitterUnused,
+                        Element::MirrorLeft => Cell::MirrorLeft,
+                        Element::MirrorRight => Cell::MirrorRight,
+                        Element::Absorber => Cell::Absorber,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut splits = 0;
+        self.trace_beams(edge, |row, line, arriving, leaving| {
+            let row = &mut cells[row];
+            for column in hit_splitters(line, arriving) {
+                row[column] = Cell::SplitterHit;
+                splits += 1;
+            }
+            for (cell, _) in row.iter_mut().zip(leaving).filter(|&(_, &beam)| beam) {
+                if *cell == Cell::Empty {
+                    *cell = Cell::Beam;
+                }
+            }
+        });
+        Simulation { cells, splits }
+    }
+
+    /// [`trace`](Self::trace) where each beam is only there or not.
+    fn trace_beams(&self, edge: Edge, visit: impl FnMut(usize, &[u8], &[bool], &[bool])) {
+        self.trace(edge, true, |a, b| Some(a || b), visit);
+    }
+
+    /// Propagate the beams row by row from the start marker, each carrying a
+    /// count: the beam leaving the start marker carries `one`, beams meeting
+    /// in a column are combined with `add`, which returns `None` on overflow,
+    /// and `T::default()` is no beam at all. `visit` is called with the index
+    /// of each row after the first, its contents, and the beams arriving at
+    /// and leaving it. Returns the beams leaving the bottom row, per column,
+    /// or `None` if `add` overflowed.
+    pub fn trace<T>(
+        &self,
+        edge: Edge,
+        one: T,
+        add: impl Fn(T, T) -> Option<T>,
+        mut visit: impl FnMut(usize, &[u8], &[T], &[T]),
+    ) -> Option<Vec<T>>
+    where
+        T: Copy + Default + PartialEq,
+    {
+        let (width, zero) = (self.width(), T::default());
+        let mut beams = vec![zero; width];
+        beams[self.start] = one;
+        for (row, line) in self.rows.iter().enumerate().skip(1) {
+            let mut new_beams = vec![zero; width];
+            for beam in (0..width).filter(|&beam| beams[beam] != zero) {
+                for target in Element::at(line, beam).targets(beam, width, edge) {
+                    new_beams[target] = add(new_beams[target], beams[beam])?;
+                }
+            }
+            visit(row, line, &beams, &new_beams);
+            beams = new_beams;
+        }
+        Some(beams)
+    }
+}
+
+/// Columns of the splitters in `line` reached by one of the `beams`.
+fn hit_splitters<'a, T: Default + PartialEq>(
+    line: &'a [u8],
+    beams: &'a [T],
+) -> impl Iterator<Item = usize> + 'a {
+    beams
+        .iter()
+        .enumerate()
+        .filter(move |&(column, beam)| {
+            *beam != T::default() && Element::at(line, column) == Element::Splitter
+        })
+        .map(|(column, _)| column)
+}
+
+/// A manifold with the path of every beam traced through it.
+#[derive(Debug, Clone)]
+pub struct Simulation {
+    pub cells: Vec<Vec<Cell>>,
+    /// Number of times a beam was split.
+    pub splits: usize,
+}
+
+impl Simulation {
+    /// Positions (row, column) of splitters that no beam reaches.
+    pub fn unused_splitters(&self) -> impl Iterator<Item = (usize, usize)> {
+        self.positions(Cell::SplitterUnused)
+    }
+
+    /// Positions (row, column) of splitters reached by a beam.
+    pub fn hit_splitters(&self) -> impl Iterator<Item = (usize, usize)> {
+        self.positions(Cell::SplitterHit)
+    }
+
+    fn positions(&self, cell: Cell) -> impl Iterator<Item = (usize, usize)> {
+        self.cells.iter().enumerate().flat_map(move |(y, row)| {
+            row.iter()
+                .enumerate()
+                .filter(move |&(_, &c)| c == cell)
+                .map(move |(x, _)| (y, x))
+        })
+    }
+}
+
+impl fmt::Display for Simulation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in &self.cells {
+            let line = row.iter().map(|cell| cell.symbol()).collect::<String>();
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn example() {
+        let manifold: Manifold = EXAMPLE.parse().unwrap();
+        let simulation = manifold.simulate(Edge::Drop);
+        assert_eq!(simulation.splits, 21);
+        assert_eq!(simulation.hit_splitters().count(), 21);
+        assert_eq!(simulation.unused_splitters().collect::<Vec<_>>(), [(14, 9)]);
+    }
+
+    #[test]
+    fn edges() {
+        let manifold: Manifold = "S..\n^..\n...".parse().unwrap();
+        let rendered = |edge| manifold.simulate(edge).to_string();
+        assert_eq!(rendered(Edge::Drop), "S..\n^|.\n.|.\n");
+        assert_eq!(rendered(Edge::Clamp), "S..\n^|.\n||.\n");
+        assert_eq!(rendered(Edge::Wrap), "S..\n^||\n.||\n");
+    }
+
+    #[test]
+    fn other_elements() {
+        let manifold: Manifold = "..S..\n../..\n.\\...\n..#..\n.....".parse().unwrap();
+        let rendered = manifold.simulate(Edge::Drop).to_string();
+        assert_eq!(rendered, "..S..\n.|/..\n.\\|..\n..#..\n.....\n");
+    }
+
+    #[test]
+    fn example_render() {
+        let manifold: Manifold = EXAMPLE.parse().unwrap();
+        let rendered = manifold.simulate(Edge::Drop).to_string();
+        let lines = rendered.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], ".......S.......");
+        assert_eq!(lines[1], ".......|.......");
+        assert_eq!(lines[2], "......|^|......");
+        assert_eq!(lines[14], "|^|^|^|^|x|||^|");
+    }
+}
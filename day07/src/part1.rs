@@ -1,39 +1,15 @@
-use thiserror::Error;
-
-#[derive(Debug, Error)]
-pub enum Error {
-    #[error("Input is empty")]
-    EmptyInput,
-    #[error("Start marker 'S' not found in input")]
-    StartMarkerNotFound,
-}
+use crate::manifold::{Edge, Manifold};
+
+pub use crate::manifold::Error;
 
 pub fn solve(input: &str) -> Result<usize, Error> {
-    let mut lines = input.lines().map(&str::as_bytes);
-    let first_line = lines.next().ok_or(Error::EmptyInput)?;
-    let start = first_line
-        .iter()
-        .position(|&c| c == b'S')
-        .ok_or(Error::StartMarkerNotFound)?;
-    let mut init_beams = vec![false; first_line.len()];
-    init_beams[start] = true;
-    let (splits, _) = lines.fold((0, init_beams), |(mut splits, beams), line| {
-        let new_beams = beams.into_iter().enumerate().filter(|&(_, c)| c).fold(
-            vec![false; first_line.len()],
-            |mut new_beams, (beam, _)| {
-                if line.get(beam) == Some(&b'^') {
-                    new_beams[beam - 1] = true;
-                    new_beams[beam + 1] = true;
-                    splits += 1;
-                } else {
-                    new_beams[beam] = true;
-                }
-                new_beams
-            },
-        );
-        (splits, new_beams)
-    });
-    Ok(splits)
+    solve_with(input, Edge::default())
+}
+
+/// Count the splits with beams leaving the sides of the manifold handled by `edge`.
+pub fn solve_with(input: &str, edge: Edge) -> Result<usize, Error> {
+    let manifold: Manifold = input.parse()?;
+    Ok(manifold.splits(edge))
 }
 
 #[cfg(test)]
@@ -48,6 +24,13 @@ mod tests {
         assert_eq!(result, 21);
     }
 
+    #[test]
+    fn splitter_at_edge() {
+        let input = "S..\n^..\n.^.";
+        assert_eq!(solve_with(input, Edge::Drop).unwrap(), 2);
+        assert_eq!(solve_with(input, Edge::Wrap).unwrap(), 2);
+    }
+
     #[cfg(input_txt)]
     #[cfg(part1_txt)]
     #[test]
@@ -1,43 +1,123 @@
+use crate::manifold::{self, Edge, Element, Manifold};
+use std::fmt::Write;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum Error {
-    #[error("Input is empty")]
-    EmptyInput,
-    #[error("Start marker 'S' not found in input")]
-    StartMarkerNotFound,
+    #[error(transparent)]
+    InvalidManifold(#[from] manifold::Error),
+    #[error("Timeline count overflowed")]
+    Overflow,
+    #[error("Modulus must be greater than zero")]
+    InvalidModulus,
 }
 
 pub fn solve(input: &str) -> Result<usize, Error> {
-    let mut lines = input.lines().map(&str::as_bytes);
-    let first_line = lines.next().ok_or(Error::EmptyInput)?;
-    let start = first_line
-        .iter()
-        .position(|&c| c == b'S')
-        .ok_or(Error::StartMarkerNotFound)?;
-    let mut init = vec![0; first_line.len()];
-    init[start] = 1usize;
-    Ok(lines
-        .fold(init, |beams, line| {
-            beams
-                .into_iter()
-                .enumerate()
-                .filter(|&(_, worlds)| worlds > 0)
-                .fold(
-                    vec![0; first_line.len()],
-                    |mut new_beams, (beam, worlds)| {
-                        if line.get(beam) == Some(&b'^') {
-                            new_beams[beam - 1] += worlds;
-                            new_beams[beam + 1] += worlds;
-                        } else {
-                            new_beams[beam] += worlds;
-                        }
-                        new_beams
-                    },
-                )
-        })
+    solve_with(input, Edge::default())
+}
+
+/// Count the timelines with beams leaving the sides of the manifold handled by `edge`.
+pub fn solve_with(input: &str, edge: Edge) -> Result<usize, Error> {
+    count_timelines(input, edge, 1, usize::checked_add)
+}
+
+/// Count timelines in 128 bits for manifolds too deep for `usize`.
+pub fn solve_u128(input: &str, edge: Edge) -> Result<u128, Error> {
+    count_timelines(input, edge, 1, u128::checked_add)
+}
+
+/// Count timelines modulo `modulus` (e.g. a large prime), which never overflows.
+pub fn solve_modulo(input: &str, edge: Edge, modulus: u64) -> Result<u64, Error> {
+    if modulus == 0 {
+        return Err(Error::InvalidModulus);
+    }
+    count_timelines(input, edge, 1 % modulus, |a, b| {
+        Some(((a as u128 + b as u128) % modulus as u128) as u64)
+    })
+}
+
+fn count_timelines<T>(
+    input: &str,
+    edge: Edge,
+    one: T,
+    add: impl Fn(T, T) -> Option<T>,
+) -> Result<T, Error>
+where
+    T: Copy + Default + PartialEq,
+{
+    let manifold: Manifold = input.parse()?;
+    manifold
+        .trace(edge, one, &add, |_, _, _, _| {})
+        .ok_or(Error::Overflow)?
         .into_iter()
-        .sum())
+        .try_fold(T::default(), |total, worlds| {
+            add(total, worlds).ok_or(Error::Overflow)
+        })
+}
+
+/// Number of timelines that reached a splitter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitterStats {
+    pub row: usize,
+    pub column: usize,
+    pub timelines: usize,
+}
+
+/// Where the timelines go on their way through the manifold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Statistics {
+    /// Every splitter in the manifold, including those no timeline reaches.
+    pub splitters: Vec<SplitterStats>,
+    /// Timelines leaving the bottom row, per column.
+    pub exits: Vec<usize>,
+}
+
+impl Statistics {
+    pub fn splitters_csv(&self) -> String {
+        let mut csv = String::from("row,column,timelines\n");
+        for splitter in &self.splitters {
+            writeln!(
+                csv,
+                "{},{},{}",
+                splitter.row, splitter.column, splitter.timelines
+            )
+            .unwrap();
+        }
+        csv
+    }
+
+    pub fn exits_csv(&self) -> String {
+        let mut csv = String::from("column,timelines\n");
+        for (column, timelines) in self.exits.iter().enumerate() {
+            writeln!(csv, "{column},{timelines}").unwrap();
+        }
+        csv
+    }
+}
+
+pub fn statistics(input: &str) -> Result<Statistics, Error> {
+    let manifold: Manifold = input.parse()?;
+    let mut splitters = Vec::new();
+    let exits = manifold
+        .trace(
+            Edge::default(),
+            1,
+            usize::checked_add,
+            |row, line, arriving, _| {
+                splitters.extend(
+                    line.iter()
+                        .enumerate()
+                        .filter(|&(_, &c)| Element::from(c) == Element::Splitter)
+                        .map(|(column, _)| SplitterStats {
+                            row,
+                            column,
+                            timelines: arriving.get(column).copied().unwrap_or(0),
+                        }),
+                );
+            },
+        )
+        .ok_or(Error::Overflow)?;
+    Ok(Statistics { splitters, exits })
 }
 
 #[cfg(test)]
@@ -52,6 +132,78 @@ mod tests {
         assert_eq!(result, 40);
     }
 
+    #[test]
+    fn example_statistics() {
+        let stats = statistics(EXAMPLE).unwrap();
+        assert_eq!(stats.exits.iter().sum::<usize>(), 40);
+        assert_eq!(
+            stats.exits,
+            [1, 0, 2, 0, 10, 0, 11, 0, 11, 0, 2, 1, 1, 0, 1]
+        );
+        assert_eq!(stats.splitters.len(), 22);
+        assert_eq!(
+            stats.splitters[0],
+            SplitterStats {
+                row: 2,
+                column: 7,
+                timelines: 1
+            }
+        );
+        let unreached = stats.splitters.iter().filter(|s| s.timelines == 0);
+        assert_eq!(unreached.count(), 1);
+        assert!(
+            stats
+                .exits_csv()
+                .starts_with("column,timelines\n0,1\n1,0\n")
+        );
+    }
+
+    #[test]
+    fn splitter_at_edge() {
+        let input = "S..\n^..\n...";
+        assert_eq!(solve_with(input, Edge::Drop).unwrap(), 1);
+        assert_eq!(solve_with(input, Edge::Clamp).unwrap(), 2);
+        assert_eq!(solve_with(input, Edge::Wrap).unwrap(), 2);
+        assert_eq!(solve_u128(input, Edge::Clamp).unwrap(), 2);
+        assert_eq!(solve_modulo(input, Edge::Wrap, 7).unwrap(), 2);
+    }
+
+    #[test]
+    fn example_modulo() {
+        let result = solve_modulo(EXAMPLE, Edge::Drop, 7).unwrap();
+        assert_eq!(result, 40 % 7);
+    }
+
+    /// A manifold where every row splits every beam, doubling the timelines.
+    fn deep_manifold(depth: usize) -> String {
+        let width = 2 * depth + 3;
+        let mut rows = vec![format!("{:.^width$}", "S")];
+        for row in 1..=depth {
+            let splitters = (0..width)
+                .map(|x| {
+                    let offset = x.abs_diff(depth + 1);
+                    if offset < row && (row - 1 - offset) % 2 == 0 {
+                        '^'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            rows.push(splitters);
+        }
+        rows.join("\n")
+    }
+
+    #[test]
+    fn overflow() {
+        let input = deep_manifold(70);
+        assert!(matches!(solve(&input), Err(Error::Overflow)));
+        assert_eq!(solve_u128(&input, Edge::Drop).unwrap(), 1 << 70);
+        let prime = 1_000_000_007;
+        let expected = (0..70).fold(1u64, |acc, _| acc * 2 % prime);
+        assert_eq!(solve_modulo(&input, Edge::Drop, prime).unwrap(), expected);
+    }
+
     #[cfg(input_txt)]
     #[cfg(part2_txt)]
     #[test]
@@ -1,3 +1,4 @@
+use common::random::junction_boxes;
 use criterion::{Criterion, criterion_group, criterion_main};
 use day08::{INPUT, part1, part2};
 use std::hint::black_box;
@@ -10,6 +11,26 @@ fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("solve day08 part2", |b| {
         b.iter(|| part2::solve(black_box(INPUT)));
     });
+
+    c.bench_function("solve day08 part1 spatial", |b| {
+        b.iter(|| part1::solve_spatial(black_box(INPUT), 1000));
+    });
+
+    c.bench_function("solve day08 part2 spatial", |b| {
+        b.iter(|| part2::solve_spatial(black_box(INPUT)));
+    });
+
+    // Too many boxes for sorting every pair, so only the spatial solvers.
+    let large = junction_boxes(100_000, 100_000, 1);
+    let mut group = c.benchmark_group("100k boxes");
+    group.sample_size(10);
+    group.bench_function("solve day08 part1 spatial", |b| {
+        b.iter(|| part1::solve_spatial(black_box(&large), 1000));
+    });
+    group.bench_function("solve day08 part2 spatial", |b| {
+        b.iter(|| part2::solve_spatial(black_box(&large)));
+    });
+    group.finish();
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -0,0 +1,35 @@
+//! Usage: `day08_graph [--tree] [--csv] [--pairs N]`
+//!
+//! Prints the connections between junction boxes as Graphviz DOT, or as an
+//! edge-list CSV with `--csv`. Shows the N closest pairs (default 1000) as
+//! in part 1, or the spanning tree joining every box with `--tree`.
+
+use day08::{INPUT, export::Graph, metric::Metric, part1, part2};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut tree = false;
+    let mut csv = false;
+    let mut pairs = 1000;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--tree" => tree = true,
+            "--csv" => csv = true,
+            "--pairs" => pairs = args.next().ok_or("--pairs needs a value")?.parse()?,
+            _ => return Err(format!("Unknown argument: {arg}").into()),
+        }
+    }
+    let boxes = part1::parse_input(INPUT)?;
+    let edges = if tree {
+        part2::spanning_tree(&boxes, Metric::default())?.1
+    } else {
+        part1::connect(&boxes, pairs, Metric::default())?.1
+    };
+    let graph = Graph::new(&boxes, edges);
+    if csv {
+        print!("{}", graph.edges_csv());
+    } else {
+        print!("{}", graph.to_dot());
+    }
+    Ok(())
+}
@@ -1,6 +1,19 @@
+//! Usage: `day08_part1 [--pairs N]`
+//!
+//! Connects the N closest pairs of junction boxes (default 1000) and prints
+//! the product of the sizes of the three largest circuits.
+
 use day08::{INPUT, part1};
 
-fn main() -> Result<(), part1::Error> {
-    println!("{:?}", part1::solve(INPUT, 1000)?);
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut pairs = 1000;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--pairs" => pairs = args.next().ok_or("--pairs needs a value")?.parse()?,
+            _ => return Err(format!("Unknown argument: {arg}").into()),
+        }
+    }
+    println!("{:?}", part1::solve(INPUT, pairs)?);
     Ok(())
 }
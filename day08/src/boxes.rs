@@ -0,0 +1,98 @@
+use glam::I64Vec3;
+use std::collections::HashMap;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Error parsing input: {0}")]
+    ParsingError(#[from] std::num::ParseIntError),
+    #[error("Missing X coordinate")]
+    MissingX,
+    #[error("Missing Y coordinate")]
+    MissingY,
+    #[error("Missing Z coordinate")]
+    MissingZ,
+    #[error("Junction boxes on lines {first} and {second} are at the same position")]
+    DuplicateBox { first: usize, second: usize },
+    #[error("Need at least two junction boxes to connect, found {0}")]
+    TooFewBoxes(usize),
+    #[error("Asked for {requested} pairs but {boxes} junction boxes only make {available}")]
+    TooManyPairs {
+        requested: usize,
+        boxes: usize,
+        available: usize,
+    },
+}
+
+/// Parse the junction box positions, one per line, rejecting duplicates.
+pub fn parse_input(input: &str) -> Result<Vec<I64Vec3>, Error> {
+    let junction_boxes = input
+        .lines()
+        .map(parse_line)
+        .collect::<Result<Vec<_>, Error>>()?;
+    let mut seen = HashMap::new();
+    for (line, position) in junction_boxes.iter().enumerate() {
+        if let Some(first) = seen.insert(position, line) {
+            return Err(Error::DuplicateBox {
+                first: first + 1,
+                second: line + 1,
+            });
+        }
+    }
+    Ok(junction_boxes)
+}
+
+fn parse_line(line: &str) -> Result<I64Vec3, Error> {
+    let mut parts = line.split(",");
+    let x = parts.next().ok_or(Error::MissingX)?.parse()?;
+    let y = parts.next().ok_or(Error::MissingY)?.parse()?;
+    let z = parts.next().ok_or(Error::MissingZ)?.parse()?;
+    Ok(I64Vec3::new(x, y, z))
+}
+
+/// Check there are enough boxes to connect into a single circuit.
+pub fn check_enough_boxes(junction_boxes: &[I64Vec3]) -> Result<(), Error> {
+    if junction_boxes.len() < 2 {
+        return Err(Error::TooFewBoxes(junction_boxes.len()));
+    }
+    Ok(())
+}
+
+/// Check the boxes make at least `pairs` distinct pairs.
+pub fn check_pairs(junction_boxes: &[I64Vec3], pairs: usize) -> Result<(), Error> {
+    let boxes = junction_boxes.len();
+    let available = boxes * boxes.saturating_sub(1) / 2;
+    if pairs > available {
+        return Err(Error::TooManyPairs {
+            requested: pairs,
+            boxes,
+            available,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid() {
+        assert!(matches!(parse_input("1,2"), Err(Error::MissingZ)));
+        assert!(matches!(parse_input("1,x,3"), Err(Error::ParsingError(_))));
+        assert!(matches!(
+            parse_input("1,2,3\n4,5,6\n4,5,6"),
+            Err(Error::DuplicateBox {
+                first: 2,
+                second: 3
+            })
+        ));
+        let boxes = parse_input("1,2,3\n4,5,6\n7,8,9").unwrap();
+        assert!(check_enough_boxes(&boxes).is_ok());
+        assert!(check_pairs(&boxes, 3).is_ok());
+        assert!(matches!(
+            check_pairs(&boxes, 4),
+            Err(Error::TooManyPairs { available: 3, .. })
+        ));
+    }
+}
@@ -0,0 +1,102 @@
+/// Disjoint-set of junction boxes, tracking which circuit each box is in.
+///
+/// Uses union by size and path compression, so joining boxes and looking up
+/// their circuit are effectively constant time.
+#[derive(Debug, Clone)]
+pub struct Circuits {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    count: usize,
+}
+
+impl Circuits {
+    /// Every box starts in a circuit of its own.
+    pub fn new(boxes: usize) -> Self {
+        Self {
+            parent: (0..boxes).collect(),
+            size: vec![1; boxes],
+            count: boxes,
+        }
+    }
+
+    /// The representative box of the circuit containing `i`.
+    pub fn find(&mut self, i: usize) -> usize {
+        let mut root = i;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut i = i;
+        while self.parent[i] != root {
+            i = std::mem::replace(&mut self.parent[i], root);
+        }
+        root
+    }
+
+    /// Connect the circuits containing `a` and `b`.
+    /// Returns `false` if they were already in the same circuit.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        let (large, small) = if self.size[a] >= self.size[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parent[small] = large;
+        self.size[large] += self.size[small];
+        self.count -= 1;
+        true
+    }
+
+    /// Number of circuits, counting unconnected boxes as circuits of one.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Size of the circuit containing `i`.
+    pub fn size_of(&mut self, i: usize) -> usize {
+        let root = self.find(i);
+        self.size[root]
+    }
+
+    /// Sizes of every circuit, in no particular order.
+    pub fn sizes(&self) -> Vec<usize> {
+        (0..self.parent.len())
+            .filter(|&i| self.parent[i] == i)
+            .map(|i| self.size[i])
+            .collect()
+    }
+
+    /// Boxes grouped by circuit, each group in ascending order.
+    pub fn members(&mut self) -> Vec<Vec<usize>> {
+        let mut groups = vec![Vec::new(); self.parent.len()];
+        for i in 0..self.parent.len() {
+            let root = self.find(i);
+            groups[root].push(i);
+        }
+        groups.retain(|group| !group.is_empty());
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn union() {
+        let mut circuits = Circuits::new(6);
+        assert!(circuits.union(0, 1));
+        assert!(circuits.union(2, 3));
+        assert!(circuits.union(1, 3));
+        assert!(!circuits.union(0, 2));
+        assert_eq!(circuits.count(), 3);
+        assert_eq!(circuits.size_of(3), 4);
+        let mut sizes = circuits.sizes();
+        sizes.sort_unstable();
+        assert_eq!(sizes, [1, 1, 4]);
+        assert_eq!(circuits.members(), [vec![0, 1, 2, 3], vec![4], vec![5]]);
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{circuits::Circuits, spatial::Pair};
+use glam::I64Vec3;
+use std::fmt::Write;
+
+/// Number of colours in the Graphviz `set312` colour scheme.
+const COLOURS: usize = 12;
+
+/// The junction boxes and the pairs connecting them, ready to be exported.
+#[derive(Debug, Clone)]
+pub struct Graph<'a> {
+    boxes: &'a [I64Vec3],
+    edges: Vec<Pair>,
+    /// Index of the circuit each box is in, numbered in order of their
+    /// lowest box. `None` for boxes connected to nothing.
+    circuit_of: Vec<Option<usize>>,
+}
+
+impl<'a> Graph<'a> {
+    pub fn new(boxes: &'a [I64Vec3], edges: Vec<Pair>) -> Self {
+        let mut circuits = Circuits::new(boxes.len());
+        for &(i, j, _) in &edges {
+            circuits.union(i as usize, j as usize);
+        }
+        let mut circuit_of = vec![None; boxes.len()];
+        let connected = circuits.members().into_iter().filter(|m| m.len() > 1);
+        for (circuit, members) in connected.enumerate() {
+            for i in members {
+                circuit_of[i] = Some(circuit);
+            }
+        }
+        Self {
+            boxes,
+            edges,
+            circuit_of,
+        }
+    }
+
+    /// Graphviz DOT with every box as a node, filled with a colour per
+    /// circuit, and an edge labelled with its distance for every pair.
+    /// Boxes connected to nothing are left white.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("graph circuits {\n");
+        dot.push_str("  node [style=filled, colorscheme=set312];\n");
+        for (i, p) in self.boxes.iter().enumerate() {
+            write!(dot, "  {i} [label=\"{},{},{}\"", p.x, p.y, p.z).unwrap();
+            if let Some(circuit) = self.circuit_of[i] {
+                write!(dot, ", fillcolor={}", circuit % COLOURS + 1).unwrap();
+            } else {
+                dot.push_str(", fillcolor=white");
+            }
+            dot.push_str("];\n");
+        }
+        for &(i, j, distance) in &self.edges {
+            writeln!(dot, "  {i} -- {j} [label=\"{distance}\"];").unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// One row per pair with the positions of both boxes, the distance between
+    /// them and the circuit they end up in.
+    pub fn edges_csv(&self) -> String {
+        let mut csv =
+            String::from("from,to,from_x,from_y,from_z,to_x,to_y,to_z,distance,circuit\n");
+        for &(i, j, distance) in &self.edges {
+            let (a, b) = (self.boxes[i as usize], self.boxes[j as usize]);
+            let circuit = self.circuit_of[i as usize].unwrap();
+            writeln!(
+                csv,
+                "{i},{j},{},{},{},{},{},{},{distance},{circuit}",
+                a.x, a.y, a.z, b.x, b.y, b.z
+            )
+            .unwrap();
+        }
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{metric::Metric, part1, part2};
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn example() {
+        let boxes = part1::parse_input(EXAMPLE).unwrap();
+        let (_, pairs) = part1::connect(&boxes, 10, Metric::default()).unwrap();
+        let graph = Graph::new(&boxes, pairs);
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("graph circuits {\n"));
+        assert!(dot.contains("  0 [label=\"162,817,812\", fillcolor=1];\n"));
+        assert!(dot.contains("  0 -- 19 [label=\"100427\"];\n"));
+        let csv = graph.edges_csv();
+        assert_eq!(csv.lines().count(), 11);
+        assert_eq!(
+            csv.lines().nth(1),
+            Some("0,19,162,817,812,425,690,689,100427,0")
+        );
+
+        let (_, tree) = part2::spanning_tree(&boxes, Metric::default()).unwrap();
+        let graph = Graph::new(&boxes, tree);
+        assert!(!graph.to_dot().contains("white"));
+        assert_eq!(graph.edges_csv().lines().count(), boxes.len());
+    }
+}
@@ -1,5 +1,11 @@
+pub mod boxes;
+pub mod circuits;
+pub mod export;
+pub mod metric;
 pub mod part1;
 pub mod part2;
+pub mod scoring;
+pub mod spatial;
 
 #[cfg(input_txt)]
 pub const INPUT: &str = include_str!("../input.txt");
@@ -0,0 +1,53 @@
+use glam::I64Vec3;
+
+/// How the distance between two junction boxes is measured.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Metric {
+    /// Straight-line distance, kept squared so it stays an integer. Squaring
+    /// doesn't change which pairs are closer.
+    #[default]
+    Euclidean,
+    /// Sum of the distances along each axis.
+    Manhattan,
+    /// Largest of the distances along each axis.
+    Chebyshev,
+}
+
+impl Metric {
+    pub fn distance(self, a: I64Vec3, b: I64Vec3) -> i64 {
+        let delta = (a - b).abs();
+        match self {
+            Metric::Euclidean => delta.length_squared(),
+            Metric::Manhattan => delta.element_sum(),
+            Metric::Chebyshev => delta.max_element(),
+        }
+    }
+
+    /// Smallest distance from `p` to any point in the box spanning `min` to `max`.
+    pub fn lower_bound(self, p: I64Vec3, min: I64Vec3, max: I64Vec3) -> i64 {
+        self.distance(p, p.clamp(min, max))
+    }
+
+    /// Distance between two points `length` apart along a single axis. Points
+    /// further apart than this along any axis are always further apart overall.
+    pub fn along_axis(self, length: i64) -> i64 {
+        self.distance(I64Vec3::ZERO, I64Vec3::new(length, 0, 0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance() {
+        let a = I64Vec3::new(1, 2, 3);
+        let b = I64Vec3::new(4, -2, 3);
+        assert_eq!(Metric::Euclidean.distance(a, b), 25);
+        assert_eq!(Metric::Manhattan.distance(a, b), 7);
+        assert_eq!(Metric::Chebyshev.distance(a, b), 4);
+        let (min, max) = (I64Vec3::new(0, 0, 0), I64Vec3::new(2, 2, 2));
+        assert_eq!(Metric::Manhattan.lower_bound(b, min, max), 5);
+        assert_eq!(Metric::Euclidean.lower_bound(a, min, max), 1);
+    }
+}
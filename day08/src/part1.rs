@@ -1,85 +1,75 @@
+use crate::{
+    boxes::check_pairs,
+    circuits::Circuits,
+    metric::Metric,
+    scoring::{self, Score, Scoring},
+    spatial::{self, Pair},
+};
 use glam::I64Vec3;
-use itertools::Itertools;
-use thiserror::Error;
 
-#[derive(Debug, Error)]
-pub enum Error {
-    #[error("Error parsing input: {0}")]
-    ParsingError(#[from] std::num::ParseIntError),
-    #[error("Missing X coordinate")]
-    MissingX,
-    #[error("Missing Y coordinate")]
-    MissingY,
-    #[error("Missing Z coordinate")]
-    MissingZ,
-}
+pub use crate::boxes::{Error, parse_input};
 
+/// Connect the `pairs` closest pairs of junction boxes and multiply the sizes
+/// of the three largest circuits.
+///
+/// `pairs` counts every pair connected, including those whose boxes are
+/// already in the same circuit, so it must be at most `n * (n - 1) / 2` for
+/// `n` boxes or [`Error::TooManyPairs`] is returned. Pairs the same distance
+/// apart are taken in the order of their boxes in the input, so which pairs
+/// make the cut is deterministic. Two boxes at the same position are rejected.
 pub fn solve(input: &str, pairs: usize) -> Result<usize, Error> {
-    let junction_boxes = input
-        .lines()
-        .map(parse_line)
-        .collect::<Result<Vec<_>, Error>>()?;
-    let mut distances = junction_boxes
-        .iter()
-        .enumerate()
-        .tuple_combinations()
-        .map(|((i, box_a), (j, box_b))| {
-            let distance = box_a.distance_squared(*box_b);
-            (i as u32, j as u32, distance)
-        })
-        .collect_vec();
-    distances.sort_unstable_by_key(|&(_, _, distance)| distance);
-    let circuits =
-        distances
-            .into_iter()
-            .take(pairs)
-            .fold(Vec::new(), |mut acc: Vec<Vec<u32>>, (i, j, _)| {
-                let a = acc.iter().position(|circuit| circuit.contains(&i));
-                let b = acc.iter().position(|circuit| circuit.contains(&j));
-                match (a, b) {
-                    (None, None) => {
-                        acc.push(vec![i, j]);
-                    }
-                    (Some(a), None) => {
-                        acc[a].push(j);
-                    }
-                    (None, Some(b)) => {
-                        acc[b].push(i);
-                    }
-                    (Some(a), Some(b)) if a < b => {
-                        let mut other = acc.remove(b);
-                        acc[a].append(&mut other);
-                    }
-                    (Some(a), Some(b)) if a > b => {
-                        let mut other = acc.remove(a);
-                        acc[b].append(&mut other);
-                    }
-                    _ => {
-                        // both already in the same circuit, do nothing
-                    }
-                }
-                acc
-            });
-    Ok(circuits
-        .into_iter()
-        .map(|v| v.len())
-        .sorted()
-        .rev()
-        .take(3)
-        .product())
+    let junction_boxes = parse_input(input)?;
+    let (circuits, _) = connect(&junction_boxes, pairs, Metric::default())?;
+    Ok(scoring::top_product(&circuits, 3))
+}
+
+/// Connect the `pairs` closest pairs of boxes, measured by `metric`, and
+/// score the resulting circuits.
+pub fn solve_with(
+    input: &str,
+    pairs: usize,
+    metric: Metric,
+    scoring: Scoring,
+) -> Result<Score, Error> {
+    let junction_boxes = parse_input(input)?;
+    let (circuits, connected) = connect(&junction_boxes, pairs, metric)?;
+    Ok(scoring.score(&junction_boxes, &circuits, connected.last().copied()))
+}
+
+/// Same as [`solve`], but finds the closest pairs by bucketing the boxes into
+/// a grid instead of sorting every pair, so it scales to many more boxes.
+pub fn solve_spatial(input: &str, pairs: usize) -> Result<usize, Error> {
+    let junction_boxes = parse_input(input)?;
+    check_pairs(&junction_boxes, pairs)?;
+    let mut circuits = Circuits::new(junction_boxes.len());
+    for (i, j, _) in spatial::closest_pairs(&junction_boxes, pairs, Metric::default()) {
+        circuits.union(i as usize, j as usize);
+    }
+    Ok(scoring::top_product(&circuits, 3))
 }
 
-fn parse_line(line: &str) -> Result<I64Vec3, Error> {
-    let mut parts = line.split(",");
-    let x = parts.next().ok_or(Error::MissingX)?.parse()?;
-    let y = parts.next().ok_or(Error::MissingY)?.parse()?;
-    let z = parts.next().ok_or(Error::MissingZ)?.parse()?;
-    Ok(I64Vec3::new(x, y, z))
+/// Connect the `pairs` closest pairs, returning the circuits and the pairs
+/// in the order they were connected.
+pub fn connect(
+    junction_boxes: &[I64Vec3],
+    pairs: usize,
+    metric: Metric,
+) -> Result<(Circuits, Vec<Pair>), Error> {
+    check_pairs(junction_boxes, pairs)?;
+    let mut distances = spatial::all_pairs(junction_boxes, metric);
+    distances.sort_unstable_by_key(spatial::key);
+    let mut circuits = Circuits::new(junction_boxes.len());
+    distances.truncate(pairs);
+    for &(i, j, _) in &distances {
+        circuits.union(i as usize, j as usize);
+    }
+    Ok((circuits, distances))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use common::random::junction_boxes;
 
     const EXAMPLE: &str = include_str!("../example.txt");
 
@@ -89,6 +79,76 @@ mod tests {
         assert_eq!(result, 40);
     }
 
+    #[test]
+    fn example_spatial() {
+        let result = solve_spatial(EXAMPLE, 10).unwrap();
+        assert_eq!(result, 40);
+    }
+
+    #[test]
+    fn too_many_pairs() {
+        assert!(solve(EXAMPLE, 190).is_ok());
+        assert!(matches!(
+            solve(EXAMPLE, 191),
+            Err(Error::TooManyPairs {
+                requested: 191,
+                boxes: 20,
+                available: 190
+            })
+        ));
+        assert!(matches!(
+            solve_spatial("1,2,3", 1),
+            Err(Error::TooManyPairs { available: 0, .. })
+        ));
+        assert!(matches!(
+            solve("1,2,3\n1,2,3", 1),
+            Err(Error::DuplicateBox {
+                first: 1,
+                second: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn ties() {
+        // Both outer boxes are the same distance from the middle one, so only
+        // the pair with the first box in the input is connected.
+        let input = "0,0,0\n10,0,0\n5,0,0\n100,0,0";
+        let score = solve_with(input, 1, Metric::Euclidean, Scoring::FinalEdge).unwrap();
+        let Score::FinalEdge(Some(edge)) = score else {
+            panic!("Expected the final edge, got {score:?}");
+        };
+        assert_eq!((edge.from.x, edge.to.x), (0, 5));
+        assert_eq!(solve(input, 1).unwrap(), solve_spatial(input, 1).unwrap());
+    }
+
+    #[test]
+    fn example_with() {
+        let score = solve_with(EXAMPLE, 10, Metric::Euclidean, Scoring::SizeHistogram).unwrap();
+        let Score::Histogram(histogram) = score else {
+            panic!("Expected a histogram, got {score:?}");
+        };
+        assert_eq!(
+            histogram.into_iter().collect::<Vec<_>>(),
+            [(1, 7), (2, 2), (4, 1), (5, 1)]
+        );
+        let score = solve_with(EXAMPLE, 10, Metric::Manhattan, Scoring::TopProduct(2)).unwrap();
+        assert!(matches!(score, Score::Product(_)));
+    }
+
+    #[test]
+    fn spatial_matches_sorted() {
+        for seed in 0..3 {
+            let input = junction_boxes(500, 100_000, seed);
+            for pairs in [10, 500, 2000] {
+                assert_eq!(
+                    solve_spatial(&input, pairs).unwrap(),
+                    solve(&input, pairs).unwrap()
+                );
+            }
+        }
+    }
+
     #[cfg(input_txt)]
     #[cfg(part1_txt)]
     #[test]
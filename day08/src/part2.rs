@@ -1,77 +1,76 @@
+use crate::{
+    boxes::check_enough_boxes,
+    circuits::Circuits,
+    metric::Metric,
+    scoring::{Score, Scoring},
+    spatial::{self, Pair},
+};
 use glam::I64Vec3;
-use itertools::Itertools;
-use thiserror::Error;
 
-#[derive(Debug, Error)]
-pub enum Error {
-    #[error("Error parsing input: {0}")]
-    ParsingError(#[from] std::num::ParseIntError),
-    #[error("Missing X coordinate")]
-    MissingX,
-    #[error("Missing Y coordinate")]
-    MissingY,
-    #[error("Missing Z coordinate")]
-    MissingZ,
-}
+pub use crate::boxes::{Error, parse_input};
 
+/// Connect the closest pairs of boxes until they form a single circuit and
+/// multiply the X coordinates of the last pair connected.
+///
+/// Pairs the same distance apart are connected in the order of their boxes in
+/// the input, so the result is deterministic. Inputs with fewer than two
+/// boxes or with two boxes at the same position are rejected.
 pub fn solve(input: &str) -> Result<i64, Error> {
-    let junction_boxes = input
-        .lines()
-        .map(parse_line)
-        .collect::<Result<Vec<_>, Error>>()?;
-    let mut distances = junction_boxes
-        .iter()
-        .enumerate()
-        .tuple_combinations()
-        .map(|((i, box_a), (j, box_b))| {
-            let distance = box_a.distance_squared(*box_b);
-            (i as u32, j as u32, distance)
-        })
-        .collect_vec();
-    distances.sort_unstable_by_key(|&(_, _, distance)| distance);
-    let mut circuits: Vec<Vec<u32>> = Vec::new();
-    for (i, j, _) in distances.into_iter() {
-        let a = circuits.iter().position(|circuit| circuit.contains(&i));
-        let b = circuits.iter().position(|circuit| circuit.contains(&j));
-        match (a, b) {
-            (None, None) => {
-                circuits.push(vec![i, j]);
-            }
-            (Some(a), None) => {
-                circuits[a].push(j);
-            }
-            (None, Some(b)) => {
-                circuits[b].push(i);
-            }
-            (Some(a), Some(b)) if a < b => {
-                let mut other = circuits.remove(b);
-                circuits[a].append(&mut other);
-            }
-            (Some(a), Some(b)) if a > b => {
-                let mut other = circuits.remove(a);
-                circuits[b].append(&mut other);
-            }
-            _ => {
-                // both already in the same circuit, do nothing
+    let junction_boxes = parse_input(input)?;
+    let (_, tree) = spanning_tree(&junction_boxes, Metric::default())?;
+    let &(i, j, _) = tree
+        .last()
+        .ok_or(Error::TooFewBoxes(junction_boxes.len()))?;
+    Ok(junction_boxes[i as usize].x * junction_boxes[j as usize].x)
+}
+
+/// Connect the closest pairs of boxes, measured by `metric`, until they form
+/// a single circuit, and score the result.
+pub fn solve_with(input: &str, metric: Metric, scoring: Scoring) -> Result<Score, Error> {
+    let junction_boxes = parse_input(input)?;
+    let (circuits, tree) = spanning_tree(&junction_boxes, metric)?;
+    Ok(scoring.score(&junction_boxes, &circuits, tree.last().copied()))
+}
+
+/// Connect the closest pairs until there is one circuit, returning it and
+/// the pairs that joined two circuits (the minimum spanning tree), in order.
+pub fn spanning_tree(
+    junction_boxes: &[I64Vec3],
+    metric: Metric,
+) -> Result<(Circuits, Vec<Pair>), Error> {
+    check_enough_boxes(junction_boxes)?;
+    let mut distances = spatial::all_pairs(junction_boxes, metric);
+    distances.sort_unstable_by_key(spatial::key);
+    let mut circuits = Circuits::new(junction_boxes.len());
+    let mut tree = Vec::with_capacity(junction_boxes.len().saturating_sub(1));
+    for (i, j, distance) in distances.into_iter() {
+        if circuits.union(i as usize, j as usize) {
+            tree.push((i, j, distance));
+            if circuits.count() == 1 {
+                return Ok((circuits, tree));
             }
         }
-        if circuits.len() == 1 && circuits[0].len() == junction_boxes.len() {
-            return Ok(junction_boxes[i as usize].x * junction_boxes[j as usize].x);
-        }
     }
-    unreachable!("Should not reach here");
+    Err(Error::TooFewBoxes(junction_boxes.len()))
 }
 
-fn parse_line(line: &str) -> Result<I64Vec3, Error> {
-    let mut parts = line.split(",");
-    let x = parts.next().ok_or(Error::MissingX)?.parse()?;
-    let y = parts.next().ok_or(Error::MissingY)?.parse()?;
-    let z = parts.next().ok_or(Error::MissingZ)?.parse()?;
-    Ok(I64Vec3::new(x, y, z))
+/// Same as [`solve`], but builds the minimum spanning tree directly with a
+/// k-d tree instead of sorting every pair, so it scales to 100k boxes. The
+/// last connection needed is the longest edge of that tree.
+pub fn solve_spatial(input: &str) -> Result<i64, Error> {
+    let junction_boxes = parse_input(input)?;
+    check_enough_boxes(&junction_boxes)?;
+    let tree = spatial::minimum_spanning_tree(&junction_boxes, Metric::default());
+    let &(i, j, _) = tree
+        .last()
+        .ok_or(Error::TooFewBoxes(junction_boxes.len()))?;
+    Ok(junction_boxes[i as usize].x * junction_boxes[j as usize].x)
 }
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use common::random::junction_boxes;
 
     const EXAMPLE: &str = include_str!("../example.txt");
 
@@ -81,6 +80,54 @@ mod tests {
         assert_eq!(result, 25272);
     }
 
+    #[test]
+    fn invalid_inputs() {
+        assert!(matches!(solve(""), Err(Error::TooFewBoxes(0))));
+        assert!(matches!(solve("1,2,3"), Err(Error::TooFewBoxes(1))));
+        assert!(matches!(solve_spatial("1,2,3"), Err(Error::TooFewBoxes(1))));
+        assert!(matches!(
+            solve("1,2,3\n4,5,6\n1,2,3"),
+            Err(Error::DuplicateBox {
+                first: 1,
+                second: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn ties() {
+        // Both boxes are the same distance from the middle one, so the pair
+        // with the first box in the input connects first.
+        let input = "0,0,0\n10,0,0\n5,0,0";
+        assert_eq!(solve(input).unwrap(), 50);
+        assert_eq!(solve_spatial(input).unwrap(), 50);
+    }
+
+    #[test]
+    fn example_spatial() {
+        let result = solve_spatial(EXAMPLE).unwrap();
+        assert_eq!(result, 25272);
+    }
+
+    #[test]
+    fn example_with() {
+        let score = solve_with(EXAMPLE, Metric::Euclidean, Scoring::FinalEdge).unwrap();
+        let Score::FinalEdge(Some(edge)) = score else {
+            panic!("Expected the final edge, got {score:?}");
+        };
+        assert_eq!(edge.x_product(), 25272);
+        let score = solve_with(EXAMPLE, Metric::Chebyshev, Scoring::TopProduct(3)).unwrap();
+        assert_eq!(score, Score::Product(20));
+    }
+
+    #[test]
+    fn spatial_matches_sorted() {
+        for seed in 0..3 {
+            let input = junction_boxes(500, 100_000, seed);
+            assert_eq!(solve_spatial(&input).unwrap(), solve(&input).unwrap());
+        }
+    }
+
     #[cfg(input_txt)]
     #[cfg(part2_txt)]
     #[test]
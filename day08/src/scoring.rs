@@ -0,0 +1,66 @@
+use crate::{circuits::Circuits, spatial::Pair};
+use glam::I64Vec3;
+use std::collections::BTreeMap;
+
+/// What to report about the circuits once the boxes have been connected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scoring {
+    /// Product of the sizes of the `k` largest circuits.
+    TopProduct(usize),
+    /// Number of circuits of each size.
+    SizeHistogram,
+    /// The last pair of boxes connected.
+    FinalEdge,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Score {
+    Product(usize),
+    /// Circuit size to the number of circuits of that size.
+    Histogram(BTreeMap<usize, usize>),
+    /// `None` if no pairs were connected.
+    FinalEdge(Option<FinalEdge>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FinalEdge {
+    pub from: I64Vec3,
+    pub to: I64Vec3,
+    pub distance: i64,
+}
+
+impl FinalEdge {
+    /// Product of the X coordinates of the two boxes, as asked for by part 2.
+    pub fn x_product(&self) -> i64 {
+        self.from.x * self.to.x
+    }
+}
+
+impl Scoring {
+    /// Score the `circuits` formed from `boxes`, where `last` is the last pair
+    /// that was connected.
+    pub fn score(self, boxes: &[I64Vec3], circuits: &Circuits, last: Option<Pair>) -> Score {
+        match self {
+            Scoring::TopProduct(k) => Score::Product(top_product(circuits, k)),
+            Scoring::SizeHistogram => {
+                let mut histogram = BTreeMap::new();
+                for size in circuits.sizes() {
+                    *histogram.entry(size).or_default() += 1;
+                }
+                Score::Histogram(histogram)
+            }
+            Scoring::FinalEdge => Score::FinalEdge(last.map(|(i, j, distance)| FinalEdge {
+                from: boxes[i as usize],
+                to: boxes[j as usize],
+                distance,
+            })),
+        }
+    }
+}
+
+/// Product of the sizes of the `k` largest circuits.
+pub fn top_product(circuits: &Circuits, k: usize) -> usize {
+    let mut sizes = circuits.sizes();
+    sizes.sort_unstable_by(|a, b| b.cmp(a));
+    sizes.into_iter().take(k).product()
+}
@@ -0,0 +1,301 @@
+use crate::{circuits::Circuits, metric::Metric};
+use glam::I64Vec3;
+use std::collections::HashMap;
+
+/// A pair of junction boxes (by index, lowest first) and the distance between them.
+pub type Pair = (u32, u32, i64);
+
+/// Sort key putting pairs in increasing distance, breaking ties by index.
+pub(crate) fn key(&(i, j, distance): &Pair) -> (i64, u32, u32) {
+    (distance, i, j)
+}
+
+fn pair(i: usize, j: usize, distance: i64) -> Pair {
+    (i.min(j) as u32, i.max(j) as u32, distance)
+}
+
+/// The `count` closest pairs of boxes in increasing distance.
+///
+/// Rather than measuring every pair, the boxes are bucketed into a grid of
+/// cells as wide as a search radius so only boxes in neighbouring cells need
+/// to be compared. The radius starts from an estimate based on the density of
+/// the boxes and doubles until enough pairs are found.
+pub fn closest_pairs(boxes: &[I64Vec3], count: usize, metric: Metric) -> Vec<Pair> {
+    let n = boxes.len();
+    let total = n * n.saturating_sub(1) / 2;
+    if count == 0 || n < 2 {
+        return Vec::new();
+    }
+    if count >= total {
+        let mut pairs = all_pairs(boxes, metric);
+        pairs.sort_unstable_by_key(key);
+        return pairs;
+    }
+
+    let min = boxes.iter().copied().reduce(I64Vec3::min).unwrap();
+    let max = boxes.iter().copied().reduce(I64Vec3::max).unwrap();
+    let extent = (max - min).max(I64Vec3::ONE).as_dvec3();
+    let volume = extent.x * extent.y * extent.z;
+    let estimate =
+        (3.0 * count as f64 * volume / (2.0 * std::f64::consts::PI * (n * n) as f64)).cbrt();
+    let mut radius = (estimate.ceil() as i64).max(1);
+
+    loop {
+        let mut pairs = pairs_within(boxes, min, radius, metric);
+        if pairs.len() >= count {
+            pairs.sort_unstable_by_key(key);
+            pairs.truncate(count);
+            return pairs;
+        }
+        radius *= 2;
+    }
+}
+
+/// Every pair of boxes, unsorted.
+pub(crate) fn all_pairs(boxes: &[I64Vec3], metric: Metric) -> Vec<Pair> {
+    (0..boxes.len())
+        .flat_map(|i| {
+            (i + 1..boxes.len()).map(move |j| pair(i, j, metric.distance(boxes[i], boxes[j])))
+        })
+        .collect()
+}
+
+/// Every pair of boxes no more than `radius` apart.
+fn pairs_within(boxes: &[I64Vec3], origin: I64Vec3, radius: i64, metric: Metric) -> Vec<Pair> {
+    let cell_of = |p: I64Vec3| (p - origin) / radius;
+    let mut grid: HashMap<I64Vec3, Vec<usize>> = HashMap::new();
+    for (i, &p) in boxes.iter().enumerate() {
+        grid.entry(cell_of(p)).or_default().push(i);
+    }
+    let limit = metric.along_axis(radius);
+    let mut pairs = Vec::new();
+    for (i, &p) in boxes.iter().enumerate() {
+        let cell = cell_of(p);
+        for dz in -1..=1 {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let Some(others) = grid.get(&(cell + I64Vec3::new(dx, dy, dz))) else {
+                        continue;
+                    };
+                    for &j in others.iter().filter(|&&j| j > i) {
+                        let distance = metric.distance(p, boxes[j]);
+                        if distance <= limit {
+                            pairs.push(pair(i, j, distance));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    pairs
+}
+
+/// The pairs joining every box into a single circuit with the shortest total
+/// length (the minimum spanning tree), in increasing distance.
+///
+/// Uses Borůvka's algorithm: each round every circuit finds its closest box in
+/// another circuit using a k-d tree, and all of those connections are made at
+/// once, at least halving the number of circuits.
+pub fn minimum_spanning_tree(boxes: &[I64Vec3], metric: Metric) -> Vec<Pair> {
+    let n = boxes.len();
+    let mut tree = KdTree::new(boxes, metric);
+    let mut circuits = Circuits::new(n);
+    let mut edges = Vec::with_capacity(n.saturating_sub(1));
+    let mut circuit_of = vec![0; n];
+    let mut best: Vec<Option<Pair>> = vec![None; n];
+
+    while circuits.count() > 1 {
+        for (i, circuit) in circuit_of.iter_mut().enumerate() {
+            *circuit = circuits.find(i);
+        }
+        tree.label(&circuit_of);
+        best.fill(None);
+        for i in 0..n {
+            let circuit = circuit_of[i];
+            tree.nearest_outside(i, &circuit_of, &mut best[circuit]);
+        }
+        for &(i, j, distance) in best.iter().flatten() {
+            if circuits.union(i as usize, j as usize) {
+                edges.push((i, j, distance));
+            }
+        }
+    }
+
+    edges.sort_unstable_by_key(key);
+    edges
+}
+
+const LEAF_SIZE: usize = 8;
+
+#[derive(Debug, Clone)]
+struct Node {
+    min: I64Vec3,
+    max: I64Vec3,
+    /// Range of `KdTree::order` holding the boxes under this node.
+    start: usize,
+    end: usize,
+    /// Indices of the two child nodes, `None` for leaves.
+    children: Option<(usize, usize)>,
+    /// Set when every box under this node is in the same circuit.
+    circuit: Option<usize>,
+}
+
+struct KdTree<'a> {
+    boxes: &'a [I64Vec3],
+    metric: Metric,
+    order: Vec<usize>,
+    nodes: Vec<Node>,
+}
+
+impl<'a> KdTree<'a> {
+    fn new(boxes: &'a [I64Vec3], metric: Metric) -> Self {
+        let mut tree = Self {
+            boxes,
+            metric,
+            order: (0..boxes.len()).collect(),
+            nodes: Vec::new(),
+        };
+        if !boxes.is_empty() {
+            tree.build(0, boxes.len());
+        }
+        tree
+    }
+
+    /// Build the subtree for `order[start..end]`, returning its node index.
+    fn build(&mut self, start: usize, end: usize) -> usize {
+        let points = self.order[start..end].iter().map(|&i| self.boxes[i]);
+        let min = points.clone().reduce(I64Vec3::min).unwrap();
+        let max = points.reduce(I64Vec3::max).unwrap();
+        let index = self.nodes.len();
+        self.nodes.push(Node {
+            min,
+            max,
+            start,
+            end,
+            children: None,
+            circuit: None,
+        });
+        if end - start > LEAF_SIZE {
+            let extent = max - min;
+            let axis = if extent.x >= extent.y && extent.x >= extent.z {
+                0
+            } else if extent.y >= extent.z {
+                1
+            } else {
+                2
+            };
+            let mid = (end - start) / 2;
+            let boxes = self.boxes;
+            self.order[start..end].select_nth_unstable_by_key(mid, |&i| boxes[i][axis]);
+            let left = self.build(start, start + mid);
+            let right = self.build(start + mid, end);
+            self.nodes[index].children = Some((left, right));
+        }
+        index
+    }
+
+    /// Record which nodes contain boxes from only one circuit.
+    fn label(&mut self, circuit_of: &[usize]) {
+        // Children are always pushed after their parent, so walking backwards
+        // visits every child before its parent.
+        for index in (0..self.nodes.len()).rev() {
+            let node = &self.nodes[index];
+            let circuit = match node.children {
+                Some((left, right)) => {
+                    let (a, b) = (self.nodes[left].circuit, self.nodes[right].circuit);
+                    if a == b { a } else { None }
+                }
+                None => {
+                    let mut members = self.order[node.start..node.end].iter();
+                    let first = circuit_of[*members.next().unwrap()];
+                    members.all(|&i| circuit_of[i] == first).then_some(first)
+                }
+            };
+            self.nodes[index].circuit = circuit;
+        }
+    }
+
+    /// Update `best` if a box outside the circuit of box `i` is closer to it.
+    fn nearest_outside(&self, i: usize, circuit_of: &[usize], best: &mut Option<Pair>) {
+        if !self.nodes.is_empty() {
+            self.search(0, i, circuit_of, best);
+        }
+    }
+
+    /// Smallest distance from `p` to any box under `node`.
+    fn bound(&self, node: &Node, p: I64Vec3) -> i64 {
+        self.metric.lower_bound(p, node.min, node.max)
+    }
+
+    fn search(&self, index: usize, i: usize, circuit_of: &[usize], best: &mut Option<Pair>) {
+        let node = &self.nodes[index];
+        let circuit = circuit_of[i];
+        if node.circuit == Some(circuit) {
+            return;
+        }
+        let p = self.boxes[i];
+        if best.is_some_and(|(_, _, distance)| self.bound(node, p) > distance) {
+            return;
+        }
+        match node.children {
+            Some((left, right)) => {
+                let (near, far) =
+                    if self.bound(&self.nodes[left], p) <= self.bound(&self.nodes[right], p) {
+                        (left, right)
+                    } else {
+                        (right, left)
+                    };
+                self.search(near, i, circuit_of, best);
+                self.search(far, i, circuit_of, best);
+            }
+            None => {
+                for &j in &self.order[node.start..node.end] {
+                    if circuit_of[j] != circuit {
+                        let candidate = pair(i, j, self.metric.distance(p, self.boxes[j]));
+                        if best.is_none_or(|best| key(&candidate) < key(&best)) {
+                            *best = Some(candidate);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boxes::parse_input;
+    use common::random::junction_boxes;
+    use itertools::Itertools;
+
+    const METRICS: [Metric; 3] = [Metric::Euclidean, Metric::Manhattan, Metric::Chebyshev];
+
+    #[test]
+    fn closest_pairs_matches_brute_force() {
+        for (seed, metric) in (0..5).cartesian_product(METRICS) {
+            let boxes = parse_input(&junction_boxes(300, 100_000, seed)).unwrap();
+            let mut expected = all_pairs(&boxes, metric);
+            expected.sort_unstable_by_key(key);
+            for count in [1, 10, 300, 1000, expected.len(), expected.len() + 5] {
+                let result = closest_pairs(&boxes, count, metric);
+                assert_eq!(result, expected[..count.min(expected.len())]);
+            }
+        }
+    }
+
+    #[test]
+    fn minimum_spanning_tree_matches_kruskal() {
+        for (seed, metric) in (0..5).cartesian_product(METRICS) {
+            let boxes = parse_input(&junction_boxes(300, 100_000, seed)).unwrap();
+            let mut pairs = all_pairs(&boxes, metric);
+            pairs.sort_unstable_by_key(key);
+            let mut circuits = Circuits::new(boxes.len());
+            let expected = pairs
+                .into_iter()
+                .filter(|&(i, j, _)| circuits.union(i as usize, j as usize))
+                .collect::<Vec<_>>();
+            assert_eq!(minimum_spanning_tree(&boxes, metric), expected);
+        }
+    }
+}
@@ -1,6 +1,6 @@
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{Criterion, criterion_group, criterion_main};
+use day09::{INPUT, part1, part2};
 use std::hint::black_box;
-use day09::{part1, part2, INPUT};
 
 fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("solve day09 part1", |b| {
@@ -10,6 +10,10 @@ fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("solve day09 part2", |b| {
         b.iter(|| part2::solve(black_box(INPUT)));
     });
+
+    c.bench_function("solve day09 part2 edge crossing", |b| {
+        b.iter(|| part2::solve_edge_crossing(black_box(INPUT)));
+    });
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -1,16 +1,32 @@
-use day09::INPUT;
+//! Usage: `day09_svg [--stroke-width W] [--scale S] [--output PATH]`
+//!
+//! Draws the loop of red and green tiles as SVG with the largest rectangles
+//! from part 1 (blue) and part 2 (orange), writing to PATH if given or to the
+//! terminal otherwise. The stroke width is in tiles and the scale in pixels
+//! per tile; both default to values that fit the floor into 1000 pixels.
 
-fn main() {
-    println!(
-        r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>
-<!DOCTYPE svg PUBLIC "-//W3C//DTD SVG 1.1//EN" "http://www.w3.org/Graphics/SVG/1.1/DTD/svg11.dtd">
-<svg width="100000" height="100000" xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink">
-<polyline points=""#
-    );
-    print!("{}", INPUT);
-    println!(
-        r#"" stroke="red" stroke-width="400" fill="green" />
-</svg>
-"#
-    );
+use day09::{
+    INPUT,
+    svg::{self, Options},
+};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut options = Options::default();
+    let mut output = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{arg} needs a value"));
+        match arg.as_str() {
+            "--stroke-width" => options.stroke_width = Some(value()?.parse()?),
+            "--scale" => options.scale = Some(value()?.parse()?),
+            "--output" => output = Some(value()?),
+            _ => return Err(format!("Unknown argument: {arg}").into()),
+        }
+    }
+    let svg = svg::render(INPUT, options)?;
+    match output {
+        Some(path) => std::fs::write(path, svg)?,
+        None => print!("{svg}"),
+    }
+    Ok(())
 }
@@ -0,0 +1,181 @@
+use glam::U64Vec2;
+use itertools::Itertools;
+
+/// The tiles inside the loop of red tiles, on a grid compressed to the
+/// distinct coordinates of the red tiles.
+///
+/// Along each axis the grid has a cell for every distinct coordinate and a
+/// cell for each run of coordinates between them (including before the first
+/// and after the last), so every tile in a cell is either inside the loop or
+/// outside it. A prefix sum over the cells holding outside tiles answers
+/// whether a rectangle is fully inside in constant time.
+#[derive(Debug, Clone)]
+pub struct CompressedGrid {
+    xs: Vec<u64>,
+    ys: Vec<u64>,
+    /// `outside[y][x]` is the number of cells holding outside tiles in the
+    /// cells before column `x` and row `y`, with a row and column of padding.
+    outside: Vec<Vec<u32>>,
+}
+
+impl CompressedGrid {
+    /// Build the grid for the loop through `coords`. Segments that aren't
+    /// horizontal or vertical are ignored.
+    pub fn new(coords: &[U64Vec2]) -> Self {
+        let xs = coords
+            .iter()
+            .map(|p| p.x)
+            .sorted_unstable()
+            .dedup()
+            .collect_vec();
+        let ys = coords
+            .iter()
+            .map(|p| p.y)
+            .sorted_unstable()
+            .dedup()
+            .collect_vec();
+        let (width, height) = (2 * xs.len() + 1, 2 * ys.len() + 1);
+        let mut inside = vec![vec![false; width]; height];
+
+        // Tiles on the loop itself.
+        let edges = coords.iter().circular_tuple_windows().collect_vec();
+        for &(p1, p2) in &edges {
+            if p1.x == p2.x || p1.y == p2.y {
+                let (x1, x2) = (cell(&xs, p1.x.min(p2.x)), cell(&xs, p1.x.max(p2.x)));
+                let (y1, y2) = (cell(&ys, p1.y.min(p2.y)), cell(&ys, p1.y.max(p2.y)));
+                for row in &mut inside[y1..=y2] {
+                    row[x1..=x2].fill(true);
+                }
+            }
+        }
+
+        // Tiles enclosed by the loop, found by counting the vertical segments
+        // crossed going left along each row. Coordinates are doubled so the
+        // tiles between two red tile coordinates can be represented.
+        let vertical = edges
+            .iter()
+            .filter(|(p1, p2)| p1.x == p2.x && p1.y != p2.y)
+            .map(|(p1, p2)| (2 * p1.x, 2 * p1.y.min(p2.y), 2 * p1.y.max(p2.y)))
+            .collect_vec();
+        for (row, cells) in inside.iter_mut().enumerate() {
+            let Some(y) = representative(&ys, row) else {
+                continue;
+            };
+            let crossings = vertical
+                .iter()
+                .filter(|&&(_, y1, y2)| y1 <= y && y < y2)
+                .map(|&(x, _, _)| x)
+                .sorted_unstable()
+                .collect_vec();
+            for (column, cell) in cells.iter_mut().enumerate() {
+                if let Some(x) = representative(&xs, column) {
+                    let crossed = crossings.partition_point(|&c| c < x);
+                    *cell |= crossed % 2 == 1;
+                }
+            }
+        }
+
+        let mut outside = vec![vec![0; width + 1]; height + 1];
+        for row in 0..height {
+            for column in 0..width {
+                let empty = !has_tiles(&xs, column) || !has_tiles(&ys, row);
+                let bad = !inside[row][column] && !empty;
+                outside[row + 1][column + 1] = outside[row][column + 1] + outside[row + 1][column]
+                    - outside[row][column]
+                    + bad as u32;
+            }
+        }
+
+        Self { xs, ys, outside }
+    }
+
+    /// Whether every tile in the rectangle with opposite corners `a` and `b`
+    /// is red or green.
+    pub fn contains_rectangle(&self, a: U64Vec2, b: U64Vec2) -> bool {
+        let (x1, x2) = (cell(&self.xs, a.x.min(b.x)), cell(&self.xs, a.x.max(b.x)));
+        let (y1, y2) = (cell(&self.ys, a.y.min(b.y)), cell(&self.ys, a.y.max(b.y)));
+        let o = &self.outside;
+        o[y2 + 1][x2 + 1] + o[y1][x1] - o[y1][x2 + 1] - o[y2 + 1][x1] == 0
+    }
+}
+
+/// Index of the cell holding `v`: odd for one of the `values`, even for the
+/// runs between them.
+fn cell(values: &[u64], v: u64) -> usize {
+    match values.binary_search(&v) {
+        Ok(k) => 2 * k + 1,
+        Err(k) => 2 * k,
+    }
+}
+
+/// A doubled coordinate within `cell`, or `None` for the runs before the
+/// first and after the last value, which are always outside.
+fn representative(values: &[u64], cell: usize) -> Option<u64> {
+    if cell % 2 == 1 {
+        Some(2 * values[cell / 2])
+    } else if cell == 0 || cell == 2 * values.len() {
+        None
+    } else {
+        Some(2 * values[cell / 2 - 1] + 1)
+    }
+}
+
+/// Whether any coordinate falls in `cell`.
+fn has_tiles(values: &[u64], cell: usize) -> bool {
+    if cell % 2 == 1 {
+        true
+    } else if cell == 0 {
+        values.first().is_some_and(|&first| first > 0)
+    } else if cell == 2 * values.len() {
+        true
+    } else {
+        values[cell / 2] - values[cell / 2 - 1] > 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cells() {
+        let values = [0, 2, 3];
+        let cells = (0..=4).map(|v| cell(&values, v)).collect_vec();
+        assert_eq!(cells, [1, 2, 3, 5, 6]);
+        let representatives = (0..=6).map(|c| representative(&values, c)).collect_vec();
+        assert_eq!(
+            representatives,
+            [None, Some(0), Some(1), Some(4), Some(5), Some(6), None]
+        );
+        let tiles = (0..=6).map(|c| has_tiles(&values, c)).collect_vec();
+        assert_eq!(tiles, [false, true, true, true, false, true, true]);
+        assert!(has_tiles(&[5], 0));
+    }
+
+    #[test]
+    fn contains_rectangle() {
+        // A U shape touching both axes, open at the top between x = 2 and 4.
+        let coords = [
+            (0, 0),
+            (6, 0),
+            (6, 4),
+            (4, 4),
+            (4, 1),
+            (2, 1),
+            (2, 4),
+            (0, 4),
+        ]
+        .map(|(x, y)| U64Vec2::new(x, y));
+        let grid = CompressedGrid::new(&coords);
+        let contains = |a: (u64, u64), b: (u64, u64)| {
+            grid.contains_rectangle(U64Vec2::new(a.0, a.1), U64Vec2::new(b.0, b.1))
+        };
+        assert!(contains((0, 0), (6, 1)));
+        assert!(contains((0, 0), (2, 4)));
+        assert!(contains((2, 1), (4, 1)));
+        assert!(!contains((2, 4), (4, 1)));
+        assert!(!contains((0, 0), (6, 2)));
+        assert!(!contains((3, 2), (3, 2)));
+        assert!(!contains((7, 0), (7, 0)));
+    }
+}
@@ -0,0 +1,116 @@
+use crate::{
+    compressed::CompressedGrid,
+    polygon::{self, Polygon},
+};
+use glam::U64Vec2;
+use itertools::Itertools;
+
+/// A rectangle of tiles, given by its lowest and highest corners.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rectangle {
+    pub min: U64Vec2,
+    pub max: U64Vec2,
+}
+
+impl Rectangle {
+    pub fn from_corners(a: U64Vec2, b: U64Vec2) -> Self {
+        Self {
+            min: a.min(b),
+            max: a.max(b),
+        }
+    }
+
+    /// Number of tiles in the rectangle.
+    pub fn area(&self) -> u64 {
+        (self.max.x - self.min.x + 1) * (self.max.y - self.min.y + 1)
+    }
+
+    pub fn contains(&self, tile: U64Vec2) -> bool {
+        tile.cmpge(self.min).all() && tile.cmple(self.max).all()
+    }
+}
+
+/// The red and green tiles of the floor: the loop of red tiles and every tile
+/// on or inside it.
+#[derive(Debug, Clone)]
+pub struct TileFloor {
+    polygon: Polygon,
+    grid: CompressedGrid,
+}
+
+impl TileFloor {
+    pub fn new(red_tiles: Vec<U64Vec2>) -> Result<Self, polygon::Error> {
+        let polygon = Polygon::new(red_tiles)?;
+        let grid = CompressedGrid::new(polygon.vertices());
+        Ok(Self { polygon, grid })
+    }
+
+    pub fn polygon(&self) -> &Polygon {
+        &self.polygon
+    }
+
+    /// Whether `tile` is red or green.
+    pub fn contains(&self, tile: U64Vec2) -> bool {
+        self.grid.contains_rectangle(tile, tile)
+    }
+
+    /// Whether every tile in `rectangle` is red or green.
+    pub fn contains_rectangle(&self, rectangle: Rectangle) -> bool {
+        self.grid.contains_rectangle(rectangle.min, rectangle.max)
+    }
+
+    /// Every rectangle with red tiles in opposite corners that only contains
+    /// red or green tiles.
+    pub fn rectangles(&self) -> impl Iterator<Item = Rectangle> + '_ {
+        self.polygon
+            .vertices()
+            .iter()
+            .tuple_combinations()
+            .map(|(&a, &b)| Rectangle::from_corners(a, b))
+            .filter(|&rectangle| self.contains_rectangle(rectangle))
+    }
+
+    /// The largest of the [`rectangles`](Self::rectangles), as asked for by part 2.
+    pub fn largest_rectangle(&self) -> Option<Rectangle> {
+        self.rectangles().max_by_key(Rectangle::area)
+    }
+
+    /// The largest of the [`rectangles`](Self::rectangles) that contains `tile`.
+    pub fn largest_rectangle_containing(&self, tile: U64Vec2) -> Option<Rectangle> {
+        self.rectangles()
+            .filter(|rectangle| rectangle.contains(tile))
+            .max_by_key(Rectangle::area)
+    }
+
+    /// The `k` largest of the [`rectangles`](Self::rectangles), largest first.
+    pub fn top_k(&self, k: usize) -> Vec<Rectangle> {
+        self.rectangles()
+            .k_largest_by_key(k, Rectangle::area)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part1;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn example() {
+        let floor = TileFloor::new(part1::parse_input(EXAMPLE).unwrap()).unwrap();
+        assert!(floor.contains(U64Vec2::new(8, 2)));
+        assert!(floor.contains(U64Vec2::new(11, 7)));
+        assert!(!floor.contains(U64Vec2::new(3, 2)));
+        assert!(!floor.contains(U64Vec2::new(12, 4)));
+        let rectangle = Rectangle::from_corners(U64Vec2::new(9, 5), U64Vec2::new(2, 3));
+        assert!(floor.contains_rectangle(rectangle));
+        assert_eq!(floor.largest_rectangle(), Some(rectangle));
+        let containing = floor.largest_rectangle_containing(U64Vec2::new(10, 2));
+        assert_eq!(containing.map(|r| r.area()), Some(21));
+        assert_eq!(floor.largest_rectangle_containing(U64Vec2::new(3, 2)), None);
+        let areas = floor.top_k(3).iter().map(Rectangle::area).collect_vec();
+        assert_eq!(areas, [24, 21, 18]);
+    }
+}
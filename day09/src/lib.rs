@@ -1,5 +1,9 @@
+pub mod compressed;
+pub mod floor;
 pub mod part1;
 pub mod part2;
+pub mod polygon;
+pub mod svg;
 
 #[cfg(input_txt)]
 pub const INPUT: &str = include_str!("../input.txt");
@@ -13,17 +13,29 @@ pub enum Error {
 }
 
 pub fn solve(input: &str) -> Result<u64, Error> {
-    let coords: Vec<_> = input.lines().map(parse_line).try_collect()?;
+    let coords = parse_input(input)?;
+    let (a, b) = largest_rectangle(&coords).ok_or(Error::NotFound)?;
+    Ok(area(a, b))
+}
+
+/// Opposite corners of the largest rectangle with red tiles in two corners.
+pub fn largest_rectangle(coords: &[U64Vec2]) -> Option<(U64Vec2, U64Vec2)> {
     coords
-        .into_iter()
+        .iter()
+        .copied()
         .tuple_combinations()
-        .map(|(a, b)| {
-            let w = a.x.abs_diff(b.x) + 1;
-            let h = a.y.abs_diff(b.y) + 1;
-            w * h
-        })
-        .max()
-        .ok_or(Error::NotFound)
+        .max_by_key(|&(a, b)| area(a, b))
+}
+
+/// Number of tiles in the rectangle with opposite corners `a` and `b`.
+pub fn area(a: U64Vec2, b: U64Vec2) -> u64 {
+    let w = a.x.abs_diff(b.x) + 1;
+    let h = a.y.abs_diff(b.y) + 1;
+    w * h
+}
+
+pub fn parse_input(input: &str) -> Result<Vec<U64Vec2>, Error> {
+    input.lines().map(parse_line).try_collect()
 }
 
 fn parse_line(line: &str) -> Result<U64Vec2, Error> {
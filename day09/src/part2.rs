@@ -1,3 +1,4 @@
+use crate::{floor::TileFloor, part1::area, polygon::Polygon};
 use glam::U64Vec2;
 use itertools::Itertools;
 use thiserror::Error;
@@ -10,10 +11,33 @@ pub enum Error {
     MissingComma,
     #[error("No valid result found")]
     NotFound,
+    #[error("Invalid loop of red tiles: {0}")]
+    InvalidLoop(#[from] crate::polygon::Error),
 }
 
+/// Area of the largest rectangle with red tiles in two corners that only
+/// contains red or green tiles, found with [`TileFloor::largest_rectangle`].
 pub fn solve(input: &str) -> Result<u64, Error> {
-    let coords: Vec<_> = input.lines().map(parse_line).try_collect()?;
+    let floor = parse_floor(input)?;
+    let rectangle = floor.largest_rectangle().ok_or(Error::NotFound)?;
+    Ok(rectangle.area())
+}
+
+/// The original solver, kept for comparison: same as [`solve`], but rejects a
+/// rectangle only when an edge of the loop passes through its interior.
+///
+/// It doesn't look at the tiles themselves, so it wrongly accepts rectangles
+/// lying outside the loop, and wrongly rejects rectangles crossed by an edge
+/// when the tiles either side of it are still red or green.
+pub fn solve_edge_crossing(input: &str) -> Result<u64, Error> {
+    let coords = parse_input(input)?;
+    let (a, b) = largest_rectangle_edge_crossing(&coords).ok_or(Error::NotFound)?;
+    Ok(area(a, b))
+}
+
+/// Opposite corners of the largest rectangle accepted by
+/// [`solve_edge_crossing`].
+pub fn largest_rectangle_edge_crossing(coords: &[U64Vec2]) -> Option<(U64Vec2, U64Vec2)> {
     let (mut horizontal_lines, mut vertical_lines) = coords.iter().circular_tuple_windows().fold(
         (Vec::new(), Vec::new()),
         |(mut h_lines, mut v_lines), (p1, p2)| {
@@ -28,7 +52,8 @@ pub fn solve(input: &str) -> Result<u64, Error> {
     horizontal_lines.sort_unstable();
     vertical_lines.sort_unstable();
     coords
-        .into_iter()
+        .iter()
+        .copied()
         .tuple_combinations()
         .filter(|(p1, p2)| {
             // Check if there are no other lines crossing rectangle formed by p1 and p2
@@ -47,13 +72,19 @@ pub fn solve(input: &str) -> Result<u64, Error> {
                     .take_while(|(x, _, _)| *x < x_max)
                     .any(|(_, y1, y2)| *y1 < y_max && *y2 > y_min)
         })
-        .map(|(p1, p2)| {
-            let w = p1.x.abs_diff(p2.x) + 1;
-            let h = p1.y.abs_diff(p2.y) + 1;
-            w * h
-        })
-        .max()
-        .ok_or(Error::NotFound)
+        .max_by_key(|&(p1, p2)| area(p1, p2))
+}
+
+/// Parse the loop of red tiles into the floor of red and green tiles.
+pub fn parse_floor(input: &str) -> Result<TileFloor, Error> {
+    let coords = input.lines().map(parse_line).try_collect()?;
+    Ok(TileFloor::new(coords)?)
+}
+
+/// Parse the loop of red tiles, checking it is a valid polygon.
+pub fn parse_input(input: &str) -> Result<Vec<U64Vec2>, Error> {
+    let coords = input.lines().map(parse_line).try_collect()?;
+    Ok(Polygon::new(coords)?.into_vertices())
 }
 
 fn parse_line(line: &str) -> Result<U64Vec2, Error> {
@@ -66,6 +97,7 @@ fn parse_line(line: &str) -> Result<U64Vec2, Error> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use common::random::Lcg;
 
     const EXAMPLE: &str = include_str!("../example.txt");
 
@@ -75,6 +107,117 @@ mod tests {
         assert_eq!(result, 24);
     }
 
+    #[test]
+    fn invalid_loop() {
+        let result = solve("1,1\n3,1\n4,3\n1,3");
+        assert!(matches!(
+            result,
+            Err(Error::InvalidLoop(crate::polygon::Error::Diagonal(1, 2)))
+        ));
+    }
+
+    #[test]
+    fn example_edge_crossing() {
+        let result = solve_edge_crossing(EXAMPLE).unwrap();
+        assert_eq!(result, 24);
+    }
+
+    #[test]
+    fn notch() {
+        // The edges of the notch in the top cross the largest rectangle, but
+        // are next to each other so the notch has no tiles outside the loop.
+        let input = "4,2\n4,3\n5,3\n5,5\n4,5\n4,4\n3,4\n3,5\n2,5\n2,2";
+        assert_eq!(solve(input).unwrap(), 12);
+        assert_eq!(solve_edge_crossing(input).unwrap(), 9);
+    }
+
+    /// A loop with random column widths whose top and bottom edges step up
+    /// and down at random by less than `steps`, from a height of 100 and 0.
+    /// Distinct coordinates are at least `gap` apart.
+    fn random_loop(columns: usize, steps: u64, gap: u64, seed: u64) -> String {
+        let mut rng = Lcg::new(seed);
+        let mut next = |range| rng.below(range);
+        let mut x = 1 + next(5);
+        let mut top = Vec::new();
+        let mut bottom = Vec::new();
+        let (mut previous_top, mut previous_bottom) = (None, None);
+        for _ in 0..columns {
+            let next_x = x + 1 + next(10);
+            let (t, b) = (100 - next(steps), next(steps));
+            if previous_top != Some(t) {
+                top.push((x, t));
+                top.push((next_x, t));
+            } else {
+                top.last_mut().unwrap().0 = next_x;
+            }
+            if previous_bottom != Some(b) {
+                bottom.push((x, b));
+                bottom.push((next_x, b));
+            } else {
+                bottom.last_mut().unwrap().0 = next_x;
+            }
+            (previous_top, previous_bottom) = (Some(t), Some(b));
+            x = next_x;
+        }
+        top.into_iter()
+            .chain(bottom.into_iter().rev())
+            .map(|(x, y)| format!("{},{}\n", x * gap, y * gap))
+            .collect()
+    }
+
+    /// Largest rectangle found by filling in every tile of the floor.
+    fn brute_force(input: &str) -> u64 {
+        let coords = parse_input(input).unwrap();
+        let edges = coords.iter().circular_tuple_windows().collect_vec();
+        let on_loop = |x, y| {
+            edges.iter().any(|(p1, p2)| {
+                (p1.x.min(p2.x)..=p1.x.max(p2.x)).contains(&x)
+                    && (p1.y.min(p2.y)..=p1.y.max(p2.y)).contains(&y)
+            })
+        };
+        let enclosed = |x, y| {
+            let crossings = edges.iter().filter(|(p1, p2)| {
+                p1.x == p2.x && p1.x < x && p1.y.min(p2.y) <= y && y < p1.y.max(p2.y)
+            });
+            crossings.count() % 2 == 1
+        };
+        coords
+            .iter()
+            .tuple_combinations()
+            .filter(|(a, b)| {
+                (a.x.min(b.x)..=a.x.max(b.x))
+                    .cartesian_product(a.y.min(b.y)..=a.y.max(b.y))
+                    .all(|(x, y)| on_loop(x, y) || enclosed(x, y))
+            })
+            .map(|(a, b)| area(*a, *b))
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        for seed in 0..10 {
+            let input = random_loop(10, 50, 1, seed);
+            assert_eq!(solve(&input).unwrap(), brute_force(&input), "{input}");
+        }
+    }
+
+    #[test]
+    fn matches_edge_crossing() {
+        // The old solver is right when no two edges are next to each other,
+        // so every rectangle crossed by an edge has tiles outside the loop,
+        // and the steps are too shallow for the rectangles it wrongly
+        // accepts outside the loop to be the largest.
+        for seed in 0..200 {
+            let input = random_loop(10, 10, 2, seed);
+            assert_eq!(
+                solve(&input).unwrap(),
+                solve_edge_crossing(&input).unwrap(),
+                "{input}"
+            );
+        }
+    }
+
     #[cfg(input_txt)]
     #[cfg(part2_txt)]
     #[test]
@@ -0,0 +1,191 @@
+use glam::U64Vec2;
+use itertools::Itertools;
+use std::collections::HashMap;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("Loop needs at least 4 red tiles, found {0}")]
+    TooFewTiles(usize),
+    #[error("Red tiles {0} and {1} are at the same position")]
+    DuplicateTile(usize, usize),
+    #[error("Segment from red tile {0} to {1} is not horizontal or vertical")]
+    Diagonal(usize, usize),
+    #[error(
+        "Loop is not closed: segment from the last red tile {0} back to the first is not horizontal or vertical"
+    )]
+    NotClosed(usize),
+    #[error("Segments starting at red tiles {0} and {1} intersect")]
+    SelfIntersection(usize, usize),
+}
+
+/// Direction the loop goes around, with X to the right and Y up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Clockwise,
+    CounterClockwise,
+}
+
+/// A loop of red tiles that has been checked to be closed, rectilinear and
+/// not to cross itself.
+#[derive(Debug, Clone)]
+pub struct Polygon {
+    vertices: Vec<U64Vec2>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<U64Vec2>) -> Result<Self, Error> {
+        let n = vertices.len();
+        if n < 4 {
+            return Err(Error::TooFewTiles(n));
+        }
+        let mut seen = HashMap::new();
+        for (i, vertex) in vertices.iter().enumerate() {
+            if let Some(first) = seen.insert(vertex, i) {
+                return Err(Error::DuplicateTile(first, i));
+            }
+        }
+        for (i, (a, b)) in vertices.iter().circular_tuple_windows().enumerate() {
+            if a.x != b.x && a.y != b.y {
+                return Err(if i == n - 1 {
+                    Error::NotClosed(i)
+                } else {
+                    Error::Diagonal(i, i + 1)
+                });
+            }
+        }
+        let polygon = Self { vertices };
+        if let Some((i, j)) = polygon.find_intersection() {
+            return Err(Error::SelfIntersection(i, j));
+        }
+        Ok(polygon)
+    }
+
+    pub fn vertices(&self) -> &[U64Vec2] {
+        &self.vertices
+    }
+
+    pub fn into_vertices(self) -> Vec<U64Vec2> {
+        self.vertices
+    }
+
+    /// Segments from each red tile to the next, by the index of their start.
+    fn segments(&self) -> impl Iterator<Item = (usize, (U64Vec2, U64Vec2))> + Clone + '_ {
+        self.vertices
+            .iter()
+            .copied()
+            .circular_tuple_windows()
+            .enumerate()
+    }
+
+    /// First pair of segments that touch anywhere other than the red tile
+    /// joining consecutive segments.
+    fn find_intersection(&self) -> Option<(usize, usize)> {
+        let n = self.vertices.len();
+        self.segments()
+            .tuple_combinations()
+            .find_map(|((i, s), (j, t))| {
+                let adjacent = j == i + 1 || (i == 0 && j == n - 1);
+                let crossing = if adjacent {
+                    // Consecutive segments only meet at their shared red tile,
+                    // unless the second doubles back along the first.
+                    let (first, second) = if j == i + 1 { (s, t) } else { (t, s) };
+                    let d1 = first.1.as_i64vec2() - first.0.as_i64vec2();
+                    let d2 = second.1.as_i64vec2() - second.0.as_i64vec2();
+                    d1.perp_dot(d2) == 0 && d1.dot(d2) < 0
+                } else {
+                    overlap(s.0.x, s.1.x, t.0.x, t.1.x) && overlap(s.0.y, s.1.y, t.0.y, t.1.y)
+                };
+                crossing.then_some((i, j))
+            })
+    }
+
+    /// Twice the signed area enclosed by the loop through the centres of the
+    /// red tiles, positive when counter-clockwise.
+    fn doubled_signed_area(&self) -> i128 {
+        self.segments()
+            .map(|(_, (a, b))| a.x as i128 * b.y as i128 - b.x as i128 * a.y as i128)
+            .sum()
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        if self.doubled_signed_area() > 0 {
+            Orientation::CounterClockwise
+        } else {
+            Orientation::Clockwise
+        }
+    }
+
+    /// Area enclosed by the loop through the centres of the red tiles, using
+    /// the shoelace formula.
+    pub fn area(&self) -> u64 {
+        (self.doubled_signed_area().unsigned_abs() / 2) as u64
+    }
+
+    /// Number of tiles on the loop.
+    pub fn perimeter(&self) -> u64 {
+        self.segments()
+            .map(|(_, (a, b))| a.x.abs_diff(b.x) + a.y.abs_diff(b.y))
+            .sum()
+    }
+
+    /// Number of red and green tiles, on or inside the loop. By Pick's theorem
+    /// the loop encloses `area - perimeter / 2 + 1` tiles, plus those on it.
+    pub fn tiles(&self) -> u64 {
+        self.area() + self.perimeter() / 2 + 1
+    }
+}
+
+/// Whether the closed ranges `a1..=a2` and `b1..=b2`, given in either order,
+/// share a value.
+fn overlap(a1: u64, a2: u64, b1: u64, b2: u64) -> bool {
+    a1.min(a2) <= b1.max(b2) && b1.min(b2) <= a1.max(a2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part1;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    fn polygon(input: &str) -> Result<Polygon, Error> {
+        Polygon::new(part1::parse_input(input).unwrap())
+    }
+
+    #[test]
+    fn example() {
+        let polygon = polygon(EXAMPLE).unwrap();
+        assert_eq!(polygon.orientation(), Orientation::CounterClockwise);
+        assert_eq!(polygon.area(), 30);
+        assert_eq!(polygon.perimeter(), 30);
+        assert_eq!(polygon.tiles(), 46);
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(polygon("1,1\n3,1\n3,3").unwrap_err(), Error::TooFewTiles(3));
+        assert_eq!(
+            polygon("1,1\n3,1\n3,3\n3,1\n1,3").unwrap_err(),
+            Error::DuplicateTile(1, 3)
+        );
+        assert_eq!(
+            polygon("1,1\n3,1\n4,3\n1,3").unwrap_err(),
+            Error::Diagonal(1, 2)
+        );
+        assert_eq!(
+            polygon("1,1\n3,1\n3,3\n1,3\n1,2\n0,2").unwrap_err(),
+            Error::NotClosed(5)
+        );
+        // A figure of eight crossing at (2,2).
+        assert_eq!(
+            polygon("0,0\n2,0\n2,4\n4,4\n4,2\n0,2").unwrap_err(),
+            Error::SelfIntersection(1, 4)
+        );
+        // The third segment doubles back along the second.
+        assert_eq!(
+            polygon("0,0\n4,0\n4,4\n4,2\n0,2").unwrap_err(),
+            Error::SelfIntersection(1, 2)
+        );
+    }
+}
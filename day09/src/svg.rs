@@ -0,0 +1,104 @@
+use crate::{floor::Rectangle, part1, part2};
+use glam::U64Vec2;
+use itertools::Itertools;
+use std::fmt::Write;
+
+/// Size of the longest side of the image when no scale is given, in pixels.
+const DEFAULT_SIZE: f64 = 1000.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Options {
+    /// Width of the outline of the loop and rectangles, in tiles. Defaults to
+    /// a fraction of the size of the floor.
+    pub stroke_width: Option<f64>,
+    /// Pixels per tile. Defaults to fitting the floor into 1000 pixels.
+    pub scale: Option<f64>,
+}
+
+/// Draw the loop of red and green tiles with the largest rectangles found by
+/// part 1 (blue) and part 2 (orange) on top. Each tile is one unit square, so
+/// the loop passes through the centres of the red tiles and the rectangles
+/// cover whole tiles.
+pub fn render(input: &str, options: Options) -> Result<String, part2::Error> {
+    let floor = part2::parse_floor(input)?;
+    let coords = floor.polygon().vertices();
+    // A valid loop has at least four red tiles.
+    let min = coords.iter().copied().fold(U64Vec2::MAX, U64Vec2::min);
+    let max = coords.iter().copied().fold(U64Vec2::ZERO, U64Vec2::max);
+    let size = (max - min + U64Vec2::ONE).as_dvec2();
+    let longest = size.x.max(size.y);
+    let stroke_width = options.stroke_width.unwrap_or(longest / 500.0);
+    let scale = options.scale.unwrap_or(DEFAULT_SIZE / longest);
+    let margin = stroke_width * 2.0;
+    let (x, y) = (min.x as f64 - margin, min.y as f64 - margin);
+    let (width, height) = (size.x + 2.0 * margin, size.y + 2.0 * margin);
+
+    let mut svg = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n");
+    writeln!(
+        svg,
+        r#"<svg width="{}" height="{}" viewBox="{x} {y} {width} {height}" xmlns="http://www.w3.org/2000/svg">"#,
+        width * scale,
+        height * scale,
+    )
+    .unwrap();
+    let points = coords
+        .iter()
+        .map(|p| format!("{},{}", p.x as f64 + 0.5, p.y as f64 + 0.5))
+        .join(" ");
+    writeln!(
+        svg,
+        r#"<polygon points="{points}" stroke="red" stroke-width="{stroke_width}" fill="green" />"#
+    )
+    .unwrap();
+    let part1 = part1::largest_rectangle(coords).map(|(a, b)| Rectangle::from_corners(a, b));
+    let rectangles = [(part1, "blue"), (floor.largest_rectangle(), "orange")];
+    for (
+        Rectangle {
+            min: low,
+            max: high,
+        },
+        colour,
+    ) in rectangles
+        .into_iter()
+        .filter_map(|(rectangle, colour)| Some((rectangle?, colour)))
+    {
+        writeln!(
+            svg,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" stroke="{colour}" stroke-width="{stroke_width}" fill="{colour}" fill-opacity="0.3" />"#,
+            low.x,
+            low.y,
+            high.x - low.x + 1,
+            high.y - low.y + 1,
+        )
+        .unwrap();
+    }
+    svg.push_str("</svg>\n");
+    Ok(svg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn example() {
+        let options = Options {
+            stroke_width: Some(0.5),
+            scale: Some(10.0),
+        };
+        let svg = render(EXAMPLE, options).unwrap();
+        assert!(svg.contains(r#"<svg width="120" height="90" viewBox="1 0 12 9""#));
+        assert!(svg.contains(r#"<polygon points="7.5,1.5 11.5,1.5 "#));
+        assert!(svg.contains(r#"<rect x="2" y="3" width="10" height="5" stroke="blue""#));
+        assert!(svg.contains(r#"<rect x="2" y="3" width="8" height="3" stroke="orange""#));
+    }
+
+    #[test]
+    fn notch() {
+        let input = "4,2\n4,3\n5,3\n5,5\n4,5\n4,4\n3,4\n3,5\n2,5\n2,2";
+        let svg = render(input, Options::default()).unwrap();
+        assert!(svg.contains(r#"<rect x="2" y="2" width="3" height="4" stroke="orange""#));
+    }
+}
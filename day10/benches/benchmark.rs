@@ -2,7 +2,8 @@ use criterion::{Criterion, criterion_group, criterion_main};
 use day10::{INPUT, part1, part2};
 use std::hint::black_box;
 
-// Part 2 is too slow on the real input, so we benchmark on the example instead.
+// The depth-first search for part 2 is too slow on the real input, so it is
+// benchmarked on the example instead.
 const EXAMPLE: &str = include_str!("../example.txt");
 
 fn criterion_benchmark(c: &mut Criterion) {
@@ -11,7 +12,11 @@ fn criterion_benchmark(c: &mut Criterion) {
     });
 
     c.bench_function("solve day10 part2", |b| {
-        b.iter(|| part2::solve(black_box(EXAMPLE)));
+        b.iter(|| part2::solve(black_box(INPUT)));
+    });
+
+    c.bench_function("solve day10 part2 dfs", |b| {
+        b.iter(|| part2::solve_dfs(black_box(EXAMPLE)));
     });
 }
 
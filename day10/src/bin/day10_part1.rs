@@ -1,6 +1,28 @@
-use day10::{part1, INPUT};
+//! Usage: `day10_part1 [--plan]`
+//!
+//! Prints the fewest button presses that turns on the indicator lights of every
+//! machine. With `--plan`, prints which buttons to press on each machine
+//! instead, as `button×times`, after checking the plan by replaying it.
 
-fn main() -> Result<(), part1::Error> {
-    println!("{:?}", part1::solve(INPUT)?);
+use day10::{INPUT, part1};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut plan = false;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--plan" => plan = true,
+            _ => return Err(format!("Unknown argument: {arg}").into()),
+        }
+    }
+    if !plan {
+        println!("{:?}", part1::solve(INPUT)?);
+        return Ok(());
+    }
+    for (i, (line, plan)) in INPUT.lines().zip(part1::plans(INPUT)?).enumerate() {
+        if !part1::check(line, &plan)? {
+            return Err(format!("Plan for machine {} does not work: {plan}", i + 1).into());
+        }
+        println!("machine {}: {} presses: {plan}", i + 1, plan.total());
+    }
     Ok(())
 }
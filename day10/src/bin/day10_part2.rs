@@ -1,6 +1,56 @@
-use day10::{part2, INPUT};
+//! Usage: `day10_part2 [--plan | --budget SECONDS]`
+//!
+//! Prints the fewest button presses that configures the joltage counters of
+//! every machine. With `--plan`, prints which buttons to press on each machine
+//! instead, as `button×times`, after checking the plan by replaying it.
+//!
+//! With `--budget`, gives up on any machine after SECONDS, showing progress
+//! while solving and then listing the machines that ran out of time with the
+//! best number of presses found for each.
 
-fn main() -> Result<(), part2::Error> {
-    println!("{:?}", part2::solve(INPUT)?);
-    Ok(())
+use day10::{INPUT, part2};
+use std::time::Duration;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut plan = false;
+    let mut budget = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--plan" => plan = true,
+            "--budget" => {
+                let seconds = args.next().ok_or("--budget needs a value")?.parse()?;
+                budget = Some(Duration::try_from_secs_f64(seconds)?);
+            }
+            _ => return Err(format!("Unknown argument: {arg}").into()),
+        }
+    }
+    match (plan, budget) {
+        (true, Some(_)) => Err("--plan and --budget can't be used together".into()),
+        (true, None) => {
+            for (i, (line, plan)) in INPUT.lines().zip(part2::plans(INPUT)?).enumerate() {
+                if !part2::check(line, &plan)? {
+                    return Err(format!("Plan for machine {} does not work: {plan}", i + 1).into());
+                }
+                println!("machine {}: {} presses: {plan}", i + 1, plan.total());
+            }
+            Ok(())
+        }
+        (false, Some(budget)) => {
+            let report = part2::solve_within(INPUT, budget, |progress| {
+                let (machine, elapsed) = progress.slowest;
+                eprint!(
+                    "\r{}/{} machines, slowest is machine {machine} at {elapsed:.2?}",
+                    progress.done, progress.total
+                );
+            })?;
+            eprintln!();
+            print!("{report}");
+            Ok(())
+        }
+        (false, None) => {
+            println!("{:?}", part2::solve(INPUT)?);
+            Ok(())
+        }
+    }
 }
@@ -1,5 +1,8 @@
+pub mod bits;
+pub mod machine;
 pub mod part1;
 pub mod part2;
+pub mod plan;
 
 #[cfg(input_txt)]
 pub const INPUT: &str = include_str!("../input.txt");
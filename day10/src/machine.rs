@@ -0,0 +1,166 @@
+use std::{fmt, str::FromStr};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("Error parsing input: {0}")]
+    ParsingError(#[from] std::num::ParseIntError),
+    #[error("Missing delimiter: {0}")]
+    MissingDelimiter(&'static str),
+    #[error("Invalid indicator light {0:?}, expected '.' or '#'")]
+    InvalidLight(char),
+    #[error("Machine has {lights} indicator lights but {joltages} joltage counters")]
+    CounterMismatch { lights: usize, joltages: usize },
+    #[error("Button {button} wires up light {index}, but there are only {lights}")]
+    ButtonOutOfRange {
+        button: usize,
+        index: usize,
+        lights: usize,
+    },
+}
+
+/// One line of the manual: the indicator light diagram, the button wiring
+/// schematics and the joltage requirements.
+///
+/// Each machine has as many joltage counters as indicator lights, and every
+/// button only affects lights and counters the machine has.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Machine {
+    /// Which lights must be on, by index.
+    pub lights: Vec<bool>,
+    /// The lights and counters affected by each button.
+    pub buttons: Vec<Vec<usize>>,
+    /// Target level of each counter.
+    pub joltages: Vec<u16>,
+}
+
+impl FromStr for Machine {
+    type Err = Error;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let (lights, remain) = line.split_once("] ").ok_or(Error::MissingDelimiter("] "))?;
+        let lights = lights
+            .strip_prefix("[")
+            .ok_or(Error::MissingDelimiter("["))?
+            .chars()
+            .map(|c| match c {
+                '.' => Ok(false),
+                '#' => Ok(true),
+                _ => Err(Error::InvalidLight(c)),
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let (buttons, joltages) = remain
+            .split_once(" {")
+            .ok_or(Error::MissingDelimiter(" {"))?;
+        let buttons = buttons
+            .split_ascii_whitespace()
+            .map(|button| {
+                button
+                    .strip_prefix("(")
+                    .ok_or(Error::MissingDelimiter("("))?
+                    .strip_suffix(")")
+                    .ok_or(Error::MissingDelimiter(")"))?
+                    .split(",")
+                    .map(|index| index.parse().map_err(Error::ParsingError))
+                    .collect()
+            })
+            .collect::<Result<Vec<Vec<usize>>, Error>>()?;
+        let joltages = joltages
+            .strip_suffix("}")
+            .ok_or(Error::MissingDelimiter("}"))?
+            .split(",")
+            .map(|joltage| joltage.parse().map_err(Error::ParsingError))
+            .collect::<Result<Vec<u16>, Error>>()?;
+
+        if lights.len() != joltages.len() {
+            return Err(Error::CounterMismatch {
+                lights: lights.len(),
+                joltages: joltages.len(),
+            });
+        }
+        for (button, indices) in buttons.iter().enumerate() {
+            if let Some(&index) = indices.iter().find(|&&i| i >= lights.len()) {
+                return Err(Error::ButtonOutOfRange {
+                    button,
+                    index,
+                    lights: lights.len(),
+                });
+            }
+        }
+        Ok(Self {
+            lights,
+            buttons,
+            joltages,
+        })
+    }
+}
+
+/// Writes the machine back in the format of the manual.
+impl fmt::Display for Machine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lights = self
+            .lights
+            .iter()
+            .map(|&on| if on { '#' } else { '.' })
+            .collect::<String>();
+        write!(f, "[{lights}]")?;
+        for button in &self.buttons {
+            write!(f, " ({})", join(button))?;
+        }
+        write!(f, " {{{}}}", join(&self.joltages))
+    }
+}
+
+fn join(values: &[impl ToString]) -> String {
+    values
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn round_trip() {
+        for line in EXAMPLE.lines() {
+            let machine = line.parse::<Machine>().unwrap();
+            assert_eq!(machine.to_string(), line);
+        }
+        let machine = EXAMPLE.lines().next().unwrap().parse::<Machine>().unwrap();
+        assert_eq!(machine.lights, [false, true, true, false]);
+        assert_eq!(machine.buttons[1], [1, 3]);
+        assert_eq!(machine.joltages, [3, 5, 4, 7]);
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(
+            "[.#] (0) (2) {1,2}".parse::<Machine>().unwrap_err(),
+            Error::ButtonOutOfRange {
+                button: 1,
+                index: 2,
+                lights: 2
+            }
+        );
+        assert_eq!(
+            "[.#] (0) {1,2,3}".parse::<Machine>().unwrap_err(),
+            Error::CounterMismatch {
+                lights: 2,
+                joltages: 3
+            }
+        );
+        assert_eq!(
+            "[.x] (0) {1,2}".parse::<Machine>().unwrap_err(),
+            Error::InvalidLight('x')
+        );
+        assert_eq!(
+            "[.#] (0 {1,2}".parse::<Machine>().unwrap_err(),
+            Error::MissingDelimiter(")")
+        );
+    }
+}
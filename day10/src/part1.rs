@@ -1,71 +1,209 @@
-use std::collections::{HashSet, VecDeque};
+use crate::{
+    bits::Bits,
+    machine::{self, Machine},
+    plan::Plan,
+};
+use std::collections::VecDeque;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum Error {
-    #[error("Error parsing input: {0}")]
-    ParsingError(#[from] std::num::ParseIntError),
-    #[error("Missing delimiter: {0}")]
-    MissingDelimiter(&'static str),
+    #[error(transparent)]
+    InvalidMachine(#[from] machine::Error),
     #[error("Unable to reach target configuration")]
     UnableToReachTarget,
+    #[error(
+        "Too many combinations to search: {buttons} buttons toggle {rank} independent sets of lights"
+    )]
+    TooManyCombinations { buttons: usize, rank: usize },
 }
 
+/// Most basis vectors whose combinations are searched, on either side.
+const MAX_SEARCH_BITS: usize = 24;
+
 pub fn solve(input: &str) -> Result<usize, Error> {
     input.lines().map(process_line).sum()
 }
 
 fn process_line(line: &str) -> Result<usize, Error> {
-    let (lights, buttons) = parse_line(line)?;
-    let mut visited = HashSet::new();
-    let mut states = VecDeque::from([(0u16, 0usize)]);
-    while let Some((state, presses)) = states.pop_front() {
-        if visited.insert(state) {
-            if state == lights {
-                return Ok(presses);
-            }
-            for &button in buttons.iter() {
-                let next_state = state ^ button;
-                states.push_back((next_state, presses + 1));
+    Ok(plan(line)?.total())
+}
+
+/// The fewest presses of each machine, one plan per line.
+pub fn plans(input: &str) -> Result<Vec<Plan>, Error> {
+    input.lines().map(plan).collect()
+}
+
+/// Which buttons to press to turn on the lights of one machine. Each button
+/// is pressed at most once.
+pub fn plan(line: &str) -> Result<Plan, Error> {
+    let (lights, buttons) = to_bits(&line.parse()?);
+    let pressed = min_presses(&lights, &buttons)?;
+    let presses = (0..buttons.len()).map(|j| pressed.get(j) as u16).collect();
+    Ok(Plan::new(presses))
+}
+
+/// Whether pressing the buttons of the machine on `line` as given by `plan`
+/// turns on exactly its lights. Plans for a different number of buttons never
+/// do.
+pub fn check(line: &str, plan: &Plan) -> Result<bool, Error> {
+    let (lights, buttons) = to_bits(&line.parse()?);
+    if plan.presses().len() != buttons.len() {
+        return Ok(false);
+    }
+    let mut toggled = lights;
+    for (j, n) in plan.pressed() {
+        if n % 2 == 1 {
+            toggled.xor(&buttons[j]);
+        }
+    }
+    Ok(toggled.is_zero())
+}
+
+/// Fewest buttons to press to turn on exactly the `lights`, as a set of
+/// button indices. Pressing a button twice undoes it, so each is pressed at
+/// most once.
+///
+/// Over GF(2) this is solving `A x = lights`, where column `j` of `A` holds
+/// the lights toggled by button `j`. Gaussian elimination finds one solution,
+/// a basis of the lights the buttons can toggle (of size the rank `r`) and a
+/// basis of the button combinations that toggle nothing (the null space, of
+/// size `k`). Every solution is the first combined with some of the null
+/// space, so when `k <= r` the smallest is found by trying each of those
+/// `2^k` combinations. Otherwise there are many redundant buttons, and a
+/// breadth-first search over the `2^r` combinations of the lights is cheaper.
+fn min_presses(lights: &Bits, buttons: &[Bits]) -> Result<Bits, Error> {
+    // Reduced buttons, each with the lowest light it toggles and the original
+    // buttons combined to make it.
+    let mut basis: Vec<(usize, Bits, Bits)> = Vec::new();
+    let mut null_space = Vec::new();
+    for (j, button) in buttons.iter().enumerate() {
+        let mut toggles = button.clone();
+        let mut combination = Bits::zeros(buttons.len());
+        combination.set(j);
+        reduce(&mut toggles, &mut combination, &basis);
+        match toggles.first_one() {
+            Some(pivot) => basis.push((pivot, toggles, combination)),
+            None => null_space.push(combination),
+        }
+    }
+
+    let mut remaining = lights.clone();
+    let mut solution = Bits::zeros(buttons.len());
+    reduce(&mut remaining, &mut solution, &basis);
+    if !remaining.is_zero() {
+        return Err(Error::UnableToReachTarget);
+    }
+
+    let (rank, dimension) = (basis.len(), null_space.len());
+    if rank.min(dimension) > MAX_SEARCH_BITS {
+        return Err(Error::TooManyCombinations {
+            buttons: buttons.len(),
+            rank,
+        });
+    }
+    if dimension > rank {
+        let coordinates = buttons
+            .iter()
+            .map(|button| coordinates_in(button, &basis))
+            .collect::<Vec<_>>();
+        return Ok(shortest_combination(
+            &coordinates,
+            coordinates_in(lights, &basis),
+            rank,
+        ));
+    }
+
+    // Walk every combination of the null space in Gray code order, changing
+    // one basis vector at a time.
+    let mut best = solution.clone();
+    for step in 1..1u64 << dimension {
+        solution.xor(&null_space[step.trailing_zeros() as usize]);
+        if solution.count_ones() < best.count_ones() {
+            best = solution.clone();
+        }
+    }
+    Ok(best)
+}
+
+/// The vectors of the `basis` that combine to make `toggles`, which must be
+/// a combination of them, as a mask of their indices.
+fn coordinates_in(toggles: &Bits, basis: &[(usize, Bits, Bits)]) -> u64 {
+    let mut toggles = toggles.clone();
+    let mut mask = 0;
+    for (k, (pivot, vector, _)) in basis.iter().enumerate() {
+        if toggles.get(*pivot) {
+            toggles.xor(vector);
+            mask |= 1 << k;
+        }
+    }
+    mask
+}
+
+/// Fewest buttons whose `coordinates` combine to make `target`, found by a
+/// breadth-first search over every combination of the `rank` basis vectors.
+fn shortest_combination(coordinates: &[u64], target: u64, rank: usize) -> Bits {
+    // The button that first reached each combination, from nothing pressed.
+    let mut reached_by = vec![u32::MAX; 1 << rank];
+    let mut queue = VecDeque::from([0]);
+    while let Some(state) = queue.pop_front() {
+        if state == target {
+            break;
+        }
+        for (j, &button) in coordinates.iter().enumerate() {
+            let next = state ^ button;
+            if next != 0 && reached_by[next as usize] == u32::MAX {
+                reached_by[next as usize] = j as u32;
+                queue.push_back(next);
             }
         }
     }
-    Err(Error::UnableToReachTarget)
+    let mut pressed = Bits::zeros(coordinates.len());
+    let mut state = target;
+    while state != 0 {
+        let j = reached_by[state as usize] as usize;
+        pressed.set(j);
+        state ^= coordinates[j];
+    }
+    pressed
+}
+
+/// Clear every pivot light of the `basis` from `toggles`, recording the
+/// buttons used in `combination`.
+fn reduce(toggles: &mut Bits, combination: &mut Bits, basis: &[(usize, Bits, Bits)]) {
+    for (pivot, vector, used) in basis {
+        if toggles.get(*pivot) {
+            toggles.xor(vector);
+            combination.xor(used);
+        }
+    }
 }
 
-fn parse_line(line: &str) -> Result<(u16, Vec<u16>), Error> {
-    let (lights, remain) = line.split_once("] ").ok_or(Error::MissingDelimiter("] "))?;
-    let lights = lights
-        .strip_prefix("[")
-        .ok_or(Error::MissingDelimiter("["))?
-        .as_bytes()
+/// The lights to turn on and the lights toggled by each button.
+fn to_bits(machine: &Machine) -> (Bits, Vec<Bits>) {
+    let width = machine.lights.len();
+    let mut lights = Bits::zeros(width);
+    for (i, _) in machine.lights.iter().enumerate().filter(|&(_, &on)| on) {
+        lights.set(i);
+    }
+    let buttons = machine
+        .buttons
         .iter()
-        .rev()
-        .fold(0, |acc, &b| (acc << 1) | if b == b'#' { 1 } else { 0 });
-    let (buttons, _joltages) = remain
-        .split_once(" {")
-        .ok_or(Error::MissingDelimiter(" {"))?;
-    let buttons = buttons
-        .split_ascii_whitespace()
-        .map(|button| {
+        .map(|indices| {
+            let mut button = Bits::zeros(width);
+            for &i in indices {
+                button.flip(i);
+            }
             button
-                .strip_prefix("(")
-                .ok_or(Error::MissingDelimiter("("))?
-                .strip_suffix(")")
-                .ok_or(Error::MissingDelimiter(")"))?
-                .split(",")
-                .try_fold(0, |acc, num_str| {
-                    let num: usize = num_str.parse()?;
-                    Ok(acc | (1 << num))
-                })
         })
-        .collect::<Result<Vec<u16>, Error>>()?;
-    Ok((lights, buttons))
+        .collect();
+    (lights, buttons)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use common::random::{Lcg, machine};
 
     const EXAMPLE: &str = include_str!("../example.txt");
 
@@ -75,6 +213,72 @@ mod tests {
         assert_eq!(result, 7);
     }
 
+    #[test]
+    fn example_plans() {
+        let plans = plans(EXAMPLE).unwrap();
+        let totals = plans.iter().map(Plan::total).collect::<Vec<_>>();
+        assert_eq!(totals, [2, 3, 2]);
+        for (line, plan) in EXAMPLE.lines().zip(&plans) {
+            assert!(check(line, plan).unwrap(), "{line}: {plan}");
+        }
+        let line = EXAMPLE.lines().next().unwrap();
+        assert!(check(line, &Plan::new(vec![0, 0, 0, 0, 1, 1])).unwrap());
+        assert!(check(line, &Plan::new(vec![2, 0, 0, 0, 3, 1])).unwrap());
+        assert!(!check(line, &Plan::new(vec![0, 0, 0, 0, 1, 0])).unwrap());
+        assert!(!check(line, &Plan::new(vec![0, 0, 0, 0, 1, 1, 0])).unwrap());
+    }
+
+    /// Fewest presses found by trying every set of buttons.
+    fn brute_force(lights: &Bits, buttons: &[Bits]) -> Option<usize> {
+        (0..1u32 << buttons.len())
+            .filter(|set| {
+                let mut toggled = lights.clone();
+                for (j, button) in buttons.iter().enumerate() {
+                    if set >> j & 1 == 1 {
+                        toggled.xor(button);
+                    }
+                }
+                toggled.is_zero()
+            })
+            .map(|set| set.count_ones() as usize)
+            .min()
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        for (lights, seed) in [6, 10, 16, 70, 130].into_iter().zip(0..) {
+            for buttons in [3, 8, 14] {
+                let machine = machine(lights, buttons, 1, &mut Lcg::new(seed));
+                let (lights, buttons) = to_bits(&machine.parse().unwrap());
+                let presses = min_presses(&lights, &buttons).ok().map(|p| p.count_ones());
+                assert_eq!(presses, brute_force(&lights, &buttons), "{machine}");
+            }
+        }
+    }
+
+    #[test]
+    fn many_redundant_buttons() {
+        // Far more buttons toggling the same lights than fit in a u64 mask.
+        let redundant = vec!["(0)"; 66].join(" ");
+        let line = format!("[####] (0) (1) (2) (3) {redundant} (0,1,2,3) {{0,0,0,0}}");
+        let plan = plan(&line).unwrap();
+        assert_eq!(plan.total(), 1);
+        assert_eq!(plan.presses()[70], 1);
+        assert!(check(&line, &plan).unwrap());
+    }
+
+    #[test]
+    fn too_many_combinations() {
+        let machine = machine(30, 60, 1, &mut Lcg::new(0));
+        assert!(matches!(
+            plan(&machine),
+            Err(Error::TooManyCombinations {
+                buttons: 60,
+                rank: 30
+            })
+        ));
+    }
+
     #[cfg(input_txt)]
     #[cfg(part1_txt)]
     #[test]
@@ -1,23 +1,493 @@
+use crate::{
+    machine::{self, Machine},
+    plan::Plan,
+};
 use rayon::prelude::*;
-use std::cmp::Reverse;
+use std::{
+    cmp::Reverse,
+    fmt,
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum Error {
-    #[error("Error parsing input: {0}")]
-    ParsingError(#[from] std::num::ParseIntError),
-    #[error("Missing delimiter: {0}")]
-    MissingDelimiter(&'static str),
+    #[error(transparent)]
+    InvalidMachine(#[from] machine::Error),
     #[error("Unable to reach target configuration")]
     UnableToReachTarget,
 }
 
-pub fn solve(input: &str) -> Result<u16, Error> {
+pub fn solve(input: &str) -> Result<usize, Error> {
     input.par_lines().map(process_line).sum()
 }
 
-fn process_line(line: &str) -> Result<u16, Error> {
-    let (buttons, target_joltages) = parse_line(line)?;
+fn process_line(line: &str) -> Result<usize, Error> {
+    Ok(plan(line)?.total())
+}
+
+/// The fewest presses of each machine, one plan per line.
+pub fn plans(input: &str) -> Result<Vec<Plan>, Error> {
+    input.par_lines().map(plan).collect()
+}
+
+/// How many times to press each button to configure the joltages of one
+/// machine.
+pub fn plan(line: &str) -> Result<Plan, Error> {
+    let Machine {
+        buttons,
+        joltages: target_joltages,
+        ..
+    } = line.parse()?;
+    let presses = min_presses(&buttons, &target_joltages).ok_or(Error::UnableToReachTarget)?;
+    Ok(Plan::new(presses))
+}
+
+/// Whether pressing the buttons of the machine on `line` as given by `plan`
+/// brings every counter exactly to its target joltage. Plans for a different
+/// number of buttons never do.
+pub fn check(line: &str, plan: &Plan) -> Result<bool, Error> {
+    let Machine {
+        buttons,
+        joltages: target_joltages,
+        ..
+    } = line.parse()?;
+    if plan.presses().len() != buttons.len() {
+        return Ok(false);
+    }
+    let mut joltages = vec![0u32; target_joltages.len()];
+    for (j, n) in plan.pressed() {
+        for &i in &buttons[j] {
+            joltages[i] += n as u32;
+        }
+    }
+    Ok(joltages
+        .iter()
+        .zip(&target_joltages)
+        .all(|(&joltage, &target)| joltage == target as u32))
+}
+
+/// How solving one machine within the time budget went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Solved(Plan),
+    /// The budget ran out before the search finished. The best plan found so
+    /// far, if any, bounds the fewest presses from above.
+    OverBudget(Option<Plan>),
+}
+
+/// One machine solved by [`solve_within`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Run {
+    /// Line of the machine in the input, starting at 1.
+    pub machine: usize,
+    pub elapsed: Duration,
+    pub outcome: Outcome,
+}
+
+/// Passed to the progress callback of [`solve_within`] after each machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub done: usize,
+    pub total: usize,
+    /// The machine that took longest so far, and how long it took.
+    pub slowest: (usize, Duration),
+}
+
+/// Every machine solved by [`solve_within`], in input order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub runs: Vec<Run>,
+}
+
+impl Report {
+    /// The machines whose search ran out of time.
+    pub fn over_budget(&self) -> impl Iterator<Item = &Run> {
+        self.runs
+            .iter()
+            .filter(|run| matches!(run.outcome, Outcome::OverBudget(_)))
+    }
+
+    /// Total presses of the machines solved within the budget.
+    pub fn solved_total(&self) -> usize {
+        self.runs
+            .iter()
+            .filter_map(|run| match &run.outcome {
+                Outcome::Solved(plan) => Some(plan.total()),
+                Outcome::OverBudget(_) => None,
+            })
+            .sum()
+    }
+}
+
+/// Summarises the solved machines, then lists the machines over budget with
+/// the best number of presses found for each.
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let over_budget = self.over_budget().count();
+        writeln!(
+            f,
+            "{} of {} machines solved, {} presses",
+            self.runs.len() - over_budget,
+            self.runs.len(),
+            self.solved_total()
+        )?;
+        for run in self.over_budget() {
+            write!(
+                f,
+                "machine {} over budget after {:.2?}: ",
+                run.machine, run.elapsed
+            )?;
+            match &run.outcome {
+                Outcome::OverBudget(Some(plan)) => {
+                    writeln!(f, "best found {} presses", plan.total())?
+                }
+                _ => writeln!(f, "no plan found")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Same as [`plans`], giving up on the search of any machine after `budget`
+/// and keeping the best plan found by then. `progress` is called after each
+/// machine, from whichever thread solved it.
+pub fn solve_within(
+    input: &str,
+    budget: Duration,
+    progress: impl Fn(Progress) + Sync,
+) -> Result<Report, Error> {
+    let lines = input.lines().collect::<Vec<_>>();
+    let done = AtomicUsize::new(0);
+    let slowest = Mutex::new((0, Duration::ZERO));
+    let runs = lines
+        .par_iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let Machine {
+                buttons,
+                joltages: target_joltages,
+                ..
+            } = line.parse()?;
+            let start = Instant::now();
+            let (presses, finished) =
+                search_presses(&buttons, &target_joltages, Some(start + budget));
+            let elapsed = start.elapsed();
+            let plan = presses.map(Plan::new);
+            let outcome = match (plan, finished) {
+                (Some(plan), true) => Outcome::Solved(plan),
+                (None, true) => return Err(Error::UnableToReachTarget),
+                (plan, false) => Outcome::OverBudget(plan),
+            };
+
+            // Hold the lock while reporting so progress is seen in order.
+            let mut slowest = slowest.lock().unwrap();
+            if elapsed > slowest.1 {
+                *slowest = (i + 1, elapsed);
+            }
+            progress(Progress {
+                done: done.fetch_add(1, Ordering::Relaxed) + 1,
+                total: lines.len(),
+                slowest: *slowest,
+            });
+            Ok(Run {
+                machine: i + 1,
+                elapsed,
+                outcome,
+            })
+        })
+        .collect::<Result<_, Error>>()?;
+    Ok(Report { runs })
+}
+
+/// Fewest presses of each button that bring every counter to its target.
+///
+/// This is an integer linear program: with `A[i][j]` set when button `j`
+/// increases counter `i`, find `x >= 0` with `A x = target` minimising the
+/// total presses. Gauss-Jordan elimination expresses the presses of the pivot
+/// buttons in terms of the few free buttons left over, whose presses are then
+/// searched with branch and bound, up to the smallest target of the counters
+/// they increase.
+fn min_presses(buttons: &[Vec<usize>], target: &[u16]) -> Option<Vec<u16>> {
+    search_presses(buttons, target, None).0
+}
+
+/// Fewest presses found by [`min_presses`] before `deadline`, and whether the
+/// search finished, so that they are the fewest possible.
+fn search_presses(
+    buttons: &[Vec<usize>],
+    target: &[u16],
+    deadline: Option<Instant>,
+) -> (Option<Vec<u16>>, bool) {
+    let (rows, columns) = (target.len(), buttons.len());
+    let mut matrix = vec![vec![0i64; columns + 1]; rows];
+    for (j, button) in buttons.iter().enumerate() {
+        for &i in button {
+            matrix[i][j] = 1;
+        }
+    }
+    for (row, &joltage) in matrix.iter_mut().zip(target) {
+        row[columns] = joltage as i64;
+    }
+
+    // Fraction-free elimination, keeping the rows small by dividing out
+    // their common factors.
+    let mut pivots = Vec::new();
+    for column in 0..columns {
+        let r = pivots.len();
+        let Some(p) = (r..rows).find(|&i| matrix[i][column] != 0) else {
+            continue;
+        };
+        matrix.swap(r, p);
+        let pivot_row = matrix[r].clone();
+        for (i, row) in matrix.iter_mut().enumerate() {
+            let factor = row[column];
+            if i != r && factor != 0 {
+                for (value, &pivot) in row.iter_mut().zip(&pivot_row) {
+                    *value = *value * pivot_row[column] - pivot * factor;
+                }
+                let divisor = row.iter().fold(0, |acc, &v| gcd(acc, v));
+                if divisor > 1 {
+                    row.iter_mut().for_each(|v| *v /= divisor);
+                }
+            }
+        }
+        pivots.push(column);
+        if pivots.len() == rows {
+            break;
+        }
+    }
+    if matrix[pivots.len()..].iter().any(|row| row[columns] != 0) {
+        return (None, true);
+    }
+
+    let free = (0..columns)
+        .filter(|column| !pivots.contains(column))
+        .collect::<Vec<_>>();
+    let bounds = buttons
+        .iter()
+        .map(|button| button.iter().map(|&i| target[i] as i64).min().unwrap_or(0))
+        .collect();
+    // Each pivot row can be solved once the last free button it uses is set.
+    let mut ready = vec![Vec::new(); free.len() + 1];
+    for (r, row) in matrix.iter().take(pivots.len()).enumerate() {
+        let depth = (0..free.len()).rev().find(|&k| row[free[k]] != 0);
+        ready[depth.map_or(0, |k| k + 1)].push(r);
+    }
+    let search = Search::new(&matrix, &pivots, &free, bounds, ready, deadline);
+    let (presses, finished) = search.run();
+    let presses = presses.map(|presses| presses.into_iter().map(|n| n as u16).collect());
+    (presses, finished)
+}
+
+/// Branch and bound over the presses of the free buttons.
+struct Search<'a> {
+    matrix: &'a [Vec<i64>],
+    pivots: &'a [usize],
+    free: &'a [usize],
+    /// Most times each button can be pressed without overshooting a counter.
+    bounds: Vec<i64>,
+    /// Pivot rows that can be solved once the first `k` free buttons are set.
+    ready: Vec<Vec<usize>>,
+    /// The total presses are `constant + slopes[k] * presses of free[k]`
+    /// summed over the free buttons.
+    constant: f64,
+    slopes: Vec<f64>,
+    /// Smallest amount the free buttons from `k` on can add to the total.
+    least_after: Vec<f64>,
+    presses: Vec<i64>,
+    best: Option<(i64, Vec<i64>)>,
+    /// When to give up, checked every [`CHECK_INTERVAL`] nodes.
+    deadline: Option<Instant>,
+    until_check: u32,
+    timed_out: bool,
+}
+
+/// Nodes searched between looking at the clock.
+const CHECK_INTERVAL: u32 = 1024;
+
+impl<'a> Search<'a> {
+    fn new(
+        matrix: &'a [Vec<i64>],
+        pivots: &'a [usize],
+        free: &'a [usize],
+        bounds: Vec<i64>,
+        ready: Vec<Vec<usize>>,
+        deadline: Option<Instant>,
+    ) -> Self {
+        let columns = bounds.len();
+        let mut constant = 0.0;
+        let mut slopes = vec![1.0; free.len()];
+        for (row, &pivot) in matrix.iter().zip(pivots) {
+            let pivot = row[pivot] as f64;
+            constant += row[columns] as f64 / pivot;
+            for (slope, &j) in slopes.iter_mut().zip(free) {
+                *slope -= row[j] as f64 / pivot;
+            }
+        }
+        let mut least_after = vec![0.0; free.len() + 1];
+        for k in (0..free.len()).rev() {
+            let furthest = slopes[k] * bounds[free[k]] as f64;
+            least_after[k] = least_after[k + 1] + furthest.min(0.0);
+        }
+        Self {
+            matrix,
+            pivots,
+            free,
+            bounds,
+            ready,
+            constant,
+            slopes,
+            least_after,
+            presses: vec![0; columns],
+            best: None,
+            deadline,
+            until_check: 0,
+            timed_out: false,
+        }
+    }
+
+    /// The best presses found, and whether the search finished in time.
+    fn run(mut self) -> (Option<Vec<i64>>, bool) {
+        self.search(0, 0, self.constant);
+        (self.best.map(|(_, presses)| presses), !self.timed_out)
+    }
+
+    /// Whether the deadline has passed, looking at the clock only every so
+    /// often.
+    fn out_of_time(&mut self) -> bool {
+        if self.timed_out {
+            return true;
+        }
+        if self.until_check == 0 {
+            self.until_check = CHECK_INTERVAL;
+            self.timed_out = self.deadline.is_some_and(|d| Instant::now() >= d);
+        }
+        self.until_check -= 1;
+        self.timed_out
+    }
+
+    /// Whether a solution whose total is at least `bound` can't beat the best.
+    fn pruned(&self, bound: f64) -> bool {
+        self.best
+            .as_ref()
+            .is_some_and(|&(best, _)| bound > best as f64 - 1.0 + 1e-6)
+    }
+
+    /// Solve the pivot rows made ready by setting the first `depth` free
+    /// buttons, then try each number of presses of the next free button.
+    /// `partial` is the total so far as a function of the free buttons.
+    fn search(&mut self, depth: usize, mut total: i64, partial: f64) {
+        if self.out_of_time() {
+            return;
+        }
+        for &r in &self.ready[depth] {
+            let pivot = self.pivots[r];
+            let (numerator, denominator) = self.remainder(r, depth);
+            if numerator % denominator != 0 {
+                return;
+            }
+            let presses = numerator / denominator;
+            if !(0..=self.bounds[pivot]).contains(&presses) {
+                return;
+            }
+            self.presses[pivot] = presses;
+            total += presses;
+        }
+        let Some(&button) = self.free.get(depth) else {
+            if !self.best.as_ref().is_some_and(|&(best, _)| total >= best) {
+                self.best = Some((total, self.presses.clone()));
+            }
+            return;
+        };
+
+        // Presses of this button that keep the pivot buttons it solves for
+        // within their bounds.
+        let (mut low, mut high) = (0, self.bounds[button]);
+        for &r in &self.ready[depth + 1] {
+            let coefficient = self.matrix[r][button];
+            let (numerator, denominator) = self.remainder(r, depth);
+            // pivot presses = (numerator - coefficient * presses) / denominator
+            let limit = self.bounds[self.pivots[r]] * denominator;
+            let (a, b) = if denominator > 0 {
+                (numerator - limit, numerator)
+            } else {
+                (numerator, numerator - limit)
+            };
+            let (a, b) = if coefficient > 0 {
+                (div_ceil(a, coefficient), div_floor(b, coefficient))
+            } else {
+                (div_ceil(b, coefficient), div_floor(a, coefficient))
+            };
+            low = low.max(a);
+            high = high.min(b);
+        }
+
+        // Try the presses that add least to the total first, stopping once
+        // even the best case can't beat the best solution found.
+        let slope = self.slopes[depth];
+        let mut values = (low..=high).collect::<Vec<_>>();
+        if slope < 0.0 {
+            values.reverse();
+        }
+        for presses in values {
+            let partial = partial + slope * presses as f64;
+            if self.timed_out || self.pruned(partial + self.least_after[depth + 1]) {
+                break;
+            }
+            self.presses[button] = presses;
+            self.search(depth + 1, total + presses, partial);
+        }
+    }
+
+    /// Right hand side of pivot row `r` with the first `depth` free buttons
+    /// moved across, and the coefficient of its pivot button.
+    fn remainder(&self, r: usize, depth: usize) -> (i64, i64) {
+        let row = &self.matrix[r];
+        let rest = self.free[..depth]
+            .iter()
+            .map(|&j| row[j] * self.presses[j])
+            .sum::<i64>();
+        (row[self.presses.len()] - rest, row[self.pivots[r]])
+    }
+}
+
+fn div_floor(a: i64, b: i64) -> i64 {
+    let (q, r) = (a / b, a % b);
+    if r != 0 && (r < 0) != (b < 0) {
+        q - 1
+    } else {
+        q
+    }
+}
+
+fn div_ceil(a: i64, b: i64) -> i64 {
+    -div_floor(-a, b)
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 { a.abs() } else { gcd(b, a % b) }
+}
+
+/// Same as [`solve`], using a depth-first search over the presses of each
+/// button instead of linear algebra. Much slower on the real input.
+pub fn solve_dfs(input: &str) -> Result<usize, Error> {
+    input
+        .par_lines()
+        .map(|line| process_line_dfs(line).map(usize::from))
+        .sum()
+}
+
+fn process_line_dfs(line: &str) -> Result<u16, Error> {
+    let Machine {
+        buttons,
+        joltages: target_joltages,
+        ..
+    } = line.parse()?;
     let buttons = reorder_buttons(buttons, target_joltages.len());
     dfs(
         &vec![0; target_joltages.len()],
@@ -96,36 +566,10 @@ fn dfs(
     }
 }
 
-fn parse_line(line: &str) -> Result<(Vec<Vec<usize>>, Vec<u16>), Error> {
-    let (_lights, remain) = line.split_once("] ").ok_or(Error::MissingDelimiter("] "))?;
-    let (buttons, joltages) = remain
-        .split_once(" {")
-        .ok_or(Error::MissingDelimiter(" {"))?;
-    let buttons = buttons
-        .split_ascii_whitespace()
-        .map(|button| {
-            button
-                .strip_prefix("(")
-                .ok_or(Error::MissingDelimiter("("))?
-                .strip_suffix(")")
-                .ok_or(Error::MissingDelimiter(")"))?
-                .split(",")
-                .map(|counter| counter.parse().map_err(Error::ParsingError))
-                .collect()
-        })
-        .collect::<Result<Vec<Vec<usize>>, Error>>()?;
-    let joltages = joltages
-        .strip_suffix("}")
-        .ok_or(Error::MissingDelimiter("}"))?
-        .split(",")
-        .map(|joltage| joltage.parse().map_err(Error::ParsingError))
-        .collect::<Result<Vec<u16>, Error>>()?;
-    Ok((buttons, joltages))
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use common::random::{Lcg, machine};
 
     const EXAMPLE: &str = include_str!("../example.txt");
 
@@ -135,10 +579,101 @@ mod tests {
         assert_eq!(result, 33);
     }
 
+    #[test]
+    fn total_beyond_u16() {
+        // Each machine needs 40000 presses, more than a u16 holds in total.
+        let input = "[#] (0) {40000}\n[#] (0) {40000}";
+        assert_eq!(solve(input).unwrap(), 80000);
+    }
+
+    #[test]
+    fn example_plans() {
+        let plans = plans(EXAMPLE).unwrap();
+        let totals = plans.iter().map(Plan::total).collect::<Vec<_>>();
+        assert_eq!(totals, [10, 12, 11]);
+        for (line, plan) in EXAMPLE.lines().zip(&plans) {
+            assert!(check(line, plan).unwrap(), "{line}: {plan}");
+        }
+        let line = EXAMPLE.lines().next().unwrap();
+        assert!(check(line, &Plan::new(vec![1, 3, 0, 3, 1, 2])).unwrap());
+        assert!(!check(line, &Plan::new(vec![1, 3, 0, 3, 1, 1])).unwrap());
+        assert!(!check(line, &Plan::new(vec![1, 3, 0, 3, 1])).unwrap());
+    }
+
+    /// Random machines with targets reachable by pressing each button up to
+    /// `max_presses` times.
+    fn random_machines(count: usize, max_presses: u64, seed: u64) -> String {
+        let mut rng = Lcg::new(seed);
+        let mut input = String::new();
+        for _ in 0..count {
+            let counters = 4 + rng.below(7) as usize;
+            let buttons = counters + rng.below(5) as usize;
+            input.push_str(&machine(counters, buttons, max_presses, &mut rng));
+            input.push('\n');
+        }
+        input
+    }
+
+    #[test]
+    fn matches_dfs() {
+        for seed in 0..5 {
+            let input = random_machines(20, 5, seed);
+            for line in input.lines() {
+                let plan = plan(line).unwrap();
+                assert!(check(line, &plan).unwrap(), "{line}: {plan}");
+                assert_eq!(
+                    plan.total(),
+                    process_line_dfs(line).unwrap() as usize,
+                    "{line}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn example_within() {
+        let calls = AtomicUsize::new(0);
+        let report = solve_within(EXAMPLE, Duration::from_secs(60), |progress| {
+            assert_eq!(progress.total, 3);
+            assert!((1..=3).contains(&progress.slowest.0));
+            calls.fetch_add(1, Ordering::Relaxed);
+        })
+        .unwrap();
+        assert_eq!(calls.into_inner(), 3);
+        assert_eq!(report.solved_total(), 33);
+        assert_eq!(report.over_budget().count(), 0);
+        let machines = report
+            .runs
+            .iter()
+            .map(|run| run.machine)
+            .collect::<Vec<_>>();
+        assert_eq!(machines, [1, 2, 3]);
+
+        let report = solve_within(EXAMPLE, Duration::ZERO, |_| {}).unwrap();
+        assert_eq!(report.over_budget().count(), 3);
+        assert!(
+            report
+                .runs
+                .iter()
+                .all(|run| run.outcome == Outcome::OverBudget(None))
+        );
+        assert!(
+            report
+                .to_string()
+                .starts_with("0 of 3 machines solved, 0 presses\n")
+        );
+        assert!(report.to_string().contains("machine 2 over budget after "));
+    }
+
+    #[test]
+    fn example_dfs() {
+        let result = solve_dfs(EXAMPLE).unwrap();
+        assert_eq!(result, 33);
+    }
+
     #[cfg(input_txt)]
     #[cfg(part2_txt)]
     #[test]
-    #[ignore = "Takes too long"]
     fn result() {
         let expected = include_str!("../part2.txt").trim().parse().unwrap();
         let result = solve(super::super::INPUT).unwrap();
@@ -0,0 +1,45 @@
+use std::fmt;
+
+/// How many times to press each button of a machine, in the order the
+/// buttons are listed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Plan {
+    presses: Vec<u16>,
+}
+
+impl Plan {
+    pub fn new(presses: Vec<u16>) -> Self {
+        Self { presses }
+    }
+
+    pub fn presses(&self) -> &[u16] {
+        &self.presses
+    }
+
+    /// Total number of button presses.
+    pub fn total(&self) -> usize {
+        self.presses.iter().map(|&n| n as usize).sum()
+    }
+
+    /// Buttons pressed at least once, with how many times, lowest index first.
+    pub fn pressed(&self) -> impl Iterator<Item = (usize, u16)> + '_ {
+        self.presses
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|&(_, n)| n > 0)
+    }
+}
+
+/// Lists the pressed buttons as `index×times`, e.g. `1×3 4×1`.
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (k, (j, n)) in self.pressed().enumerate() {
+            if k > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{j}×{n}")?;
+        }
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use common::sections::sections;
 use grid::Grid;
 use rayon::prelude::*;
 use thiserror::Error;
@@ -146,11 +147,11 @@ struct Region {
 }
 
 fn parse_input(input: &str) -> Result<(Vec<Shape>, Vec<Region>), Error> {
-    let mut sections = input.split("\n\n").collect::<Vec<_>>();
+    let mut sections = sections(input).collect::<Vec<_>>();
     let regions = sections
         .pop()
         .ok_or(Error::RegionsNotFound)?
-        .lines()
+        .into_iter()
         .map(parse_region)
         .collect::<Result<Vec<_>, _>>()?;
     let shapes = sections
@@ -160,8 +161,8 @@ fn parse_input(input: &str) -> Result<(Vec<Shape>, Vec<Region>), Error> {
     Ok((shapes, regions))
 }
 
-fn parse_shape(section: &str) -> Result<Shape, Error> {
-    let mut lines = section.lines();
+fn parse_shape(section: Vec<&str>) -> Result<Shape, Error> {
+    let mut lines = section.into_iter();
     let _id = lines
         .next()
         .ok_or(Error::ShapeIdNotFound)?
@@ -246,6 +247,12 @@ mod tests {
         assert_eq!(result, 2);
     }
 
+    #[test]
+    fn example_with_crlf() {
+        let result = solve(&EXAMPLE.replace('\n', "\r\n")).unwrap();
+        assert_eq!(result, 2);
+    }
+
     #[cfg(input_txt)]
     #[cfg(part1_txt)]
     #[test]